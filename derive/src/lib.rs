@@ -0,0 +1,266 @@
+//! Derive macros for the `common` crate. Re-exported from `common` under the `frontend` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, LitStr, Path, Result,
+    Type,
+};
+
+/// Implements `common::component::popup::compare::Comparable`.
+///
+/// Every field is compared unless marked with `#[comparable(skip)]`. Skipped fields are set to `Default::default()`
+/// when reconstructing the struct. `Option` fields which are `None` are left empty, other missing fields are an error.
+///
+/// Struct attributes:
+/// - `#[comparable(crate = "path::to::common")]` Path to the `common` crate. Defaults to `::common`. Use `crate`
+///   inside of `common` itself or the new name when the dependency is renamed.
+///
+/// Field attributes:
+/// - `#[comparable(title = "Title")]` Row title. Defaults to the field name in title case.
+/// - `#[comparable(display = "text" | "image")]` Defaults to `text`.
+/// - `#[comparable(with = "path::to_fn")]` Converts the value into a `CompareValue` with `fn(&T) -> CompareValue`
+///   instead of `Into`. For `Option<T>` fields it's called with the inner value.
+/// - `#[comparable(skip)]`
+#[proc_macro_derive(Comparable, attributes(comparable))]
+pub fn derive_comparable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct FieldInfo {
+    ident: syn::Ident,
+    name: String,
+    title: String,
+    is_image: bool,
+    with: Option<Path>,
+    skip: bool,
+    is_option: bool,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "Comparable can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(
+            input.span(),
+            "Comparable requires a struct with named fields",
+        ));
+    };
+
+    let fields = named
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<Result<Vec<_>>>()?;
+
+    let common = parse_crate_path(&input)?;
+    let krate = quote!(#common::component::popup::compare);
+
+    let displays = fields.iter().filter(|v| !v.skip).map(|field| {
+        let name = &field.name;
+        let title = &field.title;
+
+        let display = if field.is_image {
+            quote!(#krate::CompareDisplay::Image)
+        } else {
+            quote!(#krate::CompareDisplay::Text)
+        };
+
+        quote!((#name, #title, #display))
+    });
+
+    let inserts = fields.iter().filter(|v| !v.skip).map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+
+        let to_map_value = |value: TokenStream2| match &field.with {
+            Some(with) => quote! {
+                #krate::MapValue {
+                    original_value: #krate::serde_json::to_value(#value)?,
+                    display_value: #with(#value),
+                }
+            },
+            None => quote!(#krate::morph_map_value(::std::clone::Clone::clone(#value))?),
+        };
+
+        if field.is_option {
+            let map_value = to_map_value(quote!(value));
+
+            quote! {
+                if let ::std::option::Option::Some(value) = &self.#ident {
+                    map.insert(#name, #map_value);
+                }
+            }
+        } else {
+            let map_value = to_map_value(quote!(&self.#ident));
+
+            quote! {
+                map.insert(#name, #map_value);
+            }
+        }
+    });
+
+    let constructs = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+
+        if field.skip {
+            quote!(#ident: ::std::default::Default::default())
+        } else if !field.is_option {
+            quote! {
+                #ident: match map.remove(#name) {
+                    ::std::option::Option::Some(value) => #krate::serde_json::from_value(value)?,
+                    ::std::option::Option::None => {
+                        return ::std::result::Result::Err(
+                            <#krate::serde_json::Error as #krate::serde::de::Error>::missing_field(#name),
+                        );
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #ident: match map.remove(#name) {
+                    ::std::option::Option::Some(value) => #krate::serde_json::from_value(value)?,
+                    ::std::option::Option::None => ::std::default::Default::default(),
+                }
+            }
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::Comparable for #ident #ty_generics #where_clause {
+            fn compare_displays() -> ::std::vec::Vec<(&'static str, &'static str, #krate::CompareDisplay)> {
+                ::std::vec![#(#displays),*]
+            }
+
+            fn create_from_comparison(
+                mut map: ::std::collections::HashMap<&'static str, #krate::serde_json::Value>,
+            ) -> #krate::serde_json::Result<Self> {
+                ::std::result::Result::Ok(Self {
+                    #(#constructs,)*
+                })
+            }
+
+            fn create_map(&self) -> #krate::serde_json::Result<#krate::MapContainer> {
+                let mut map = #krate::MapContainer::new();
+
+                #(#inserts)*
+
+                ::std::result::Result::Ok(map)
+            }
+        }
+    })
+}
+
+/// Reads `#[comparable(crate = "...")]` from the struct.
+fn parse_crate_path(input: &DeriveInput) -> Result<TokenStream2> {
+    let mut path = quote!(::common);
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|v| v.path().is_ident("comparable"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let value = meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+                path = quote!(#value);
+            } else {
+                return Err(meta.error("unknown comparable attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(path)
+}
+
+fn parse_field(field: &syn::Field) -> Result<FieldInfo> {
+    let Some(ident) = field.ident.clone() else {
+        return Err(Error::new(field.span(), "expected a named field"));
+    };
+
+    let name = ident.to_string().trim_start_matches("r#").to_string();
+
+    let mut info = FieldInfo {
+        title: title_case(&name),
+        name,
+        ident,
+        is_image: false,
+        with: None,
+        skip: false,
+        is_option: is_option(&field.ty),
+    };
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|v| v.path().is_ident("comparable"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                info.title = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("display") {
+                let value = meta.value()?.parse::<LitStr>()?;
+
+                info.is_image = match value.value().as_str() {
+                    "text" => false,
+                    "image" => true,
+                    _ => return Err(Error::new(value.span(), "expected \"text\" or \"image\"")),
+                };
+            } else if meta.path.is_ident("with") {
+                info.with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else if meta.path.is_ident("skip") {
+                info.skip = true;
+            } else {
+                return Err(meta.error("unknown comparable attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(info)
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.path
+        .segments
+        .last()
+        .is_some_and(|v| v.ident == "Option")
+}
+
+/// "thumb_url" -> "Thumb Url"
+fn title_case(value: &str) -> String {
+    value
+        .split('_')
+        .filter(|v| !v.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
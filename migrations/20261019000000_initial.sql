@@ -0,0 +1,109 @@
+-- Shared schema for the librarian and reader servers.
+
+CREATE TABLE IF NOT EXISTS person (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	source TEXT NOT NULL,
+
+	name TEXT NOT NULL COLLATE NOCASE,
+	description TEXT,
+	birth_date DATE,
+
+	thumb_url TEXT,
+
+	updated_at DATETIME NOT NULL,
+	created_at DATETIME NOT NULL,
+	deleted_at DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS person_alt (
+	person_id INTEGER NOT NULL REFERENCES person(id) ON DELETE CASCADE,
+
+	name TEXT NOT NULL COLLATE NOCASE,
+
+	PRIMARY KEY (person_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS book (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	source TEXT NOT NULL,
+
+	title TEXT,
+	clean_title TEXT,
+	description TEXT,
+	rating REAL NOT NULL DEFAULT 0,
+
+	thumb_url TEXT,
+
+	isbn_10 TEXT,
+	isbn_13 TEXT,
+
+	display_person_id INTEGER REFERENCES person(id) ON DELETE SET NULL,
+	publisher TEXT,
+
+	is_public INTEGER NOT NULL DEFAULT 0,
+	edition_count INTEGER NOT NULL DEFAULT 0,
+
+	available_at DATE,
+	language INTEGER NOT NULL DEFAULT 0,
+
+	created_at DATETIME NOT NULL,
+	updated_at DATETIME NOT NULL,
+	deleted_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS book_updated_at ON book (updated_at);
+CREATE INDEX IF NOT EXISTS book_isbn_13 ON book (isbn_13);
+CREATE INDEX IF NOT EXISTS person_updated_at ON person (updated_at);
+
+CREATE TABLE IF NOT EXISTS book_person (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	book_id INTEGER NOT NULL REFERENCES book(id) ON DELETE CASCADE,
+	person_id INTEGER NOT NULL REFERENCES person(id) ON DELETE CASCADE,
+
+	UNIQUE (book_id, person_id)
+);
+
+CREATE TABLE IF NOT EXISTS tag (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	name TEXT NOT NULL COLLATE NOCASE,
+	type_of INTEGER NOT NULL DEFAULT 0,
+
+	created_at DATETIME NOT NULL,
+	updated_at DATETIME NOT NULL,
+
+	UNIQUE (name, type_of)
+);
+
+CREATE TABLE IF NOT EXISTS book_tag (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	book_id INTEGER NOT NULL REFERENCES book(id) ON DELETE CASCADE,
+	tag_id INTEGER NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+
+	idx INTEGER NOT NULL DEFAULT 0,
+
+	created_at DATETIME NOT NULL,
+
+	UNIQUE (book_id, tag_id)
+);
+
+CREATE TABLE IF NOT EXISTS image (
+	id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+
+	path TEXT NOT NULL UNIQUE,
+
+	created_at DATETIME NOT NULL
+);
+
+-- Which book or person an image belongs to. "link" is an ImageIdType ("{id}-{type}").
+CREATE TABLE IF NOT EXISTS image_link (
+	image_id INTEGER NOT NULL REFERENCES image(id) ON DELETE CASCADE,
+
+	link TEXT NOT NULL,
+
+	PRIMARY KEY (image_id, link)
+);
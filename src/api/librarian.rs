@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+pub use patch::*;
 pub use search::*;
+pub use sync::*;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthFormLink {
@@ -40,6 +42,7 @@ mod search {
             deserialize_datetime, deserialize_datetime_opt, deserialize_naivedate_opt,
             serialize_datetime, serialize_datetime_opt, serialize_naivedate_opt,
         },
+        PersonName,
     };
     use chrono::{DateTime, NaiveDate, Utc};
     use serde::{Deserialize, Serialize};
@@ -101,6 +104,24 @@ mod search {
             deserialize_with = "deserialize_datetime"
         )]
         pub created_at: DateTime<Utc>,
+        #[serde(
+            default,
+            serialize_with = "serialize_datetime_opt",
+            deserialize_with = "deserialize_datetime_opt"
+        )]
+        pub deleted_at: Option<DateTime<Utc>>,
+    }
+
+    impl PublicAuthor {
+        /// Best [`PersonName::similarity`] against the name and all other names.
+        pub fn name_similarity(&self, value: &str) -> f64 {
+            let value = PersonName::parse(value);
+
+            std::iter::once(&self.name)
+                .chain(self.other_names.iter())
+                .map(|v| PersonName::parse(v).similarity(&value))
+                .fold(0.0, f64::max)
+        }
     }
 
     // Book
@@ -174,3 +195,426 @@ mod search {
         pub deleted_at: Option<DateTime<Utc>>,
     }
 }
+
+mod sync {
+    use std::{collections::HashMap, fmt, str::FromStr};
+
+    use crate::{
+        api::WrappingResponse,
+        error::{Error, Result as CrateResult},
+        util::{
+            deserialize_datetime, deserialize_datetime_opt, serialize_datetime,
+            serialize_datetime_opt,
+        },
+    };
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{PublicAuthor, PublicBook};
+
+    pub type PublicChangesResponse = WrappingResponse<PublicChanges>;
+
+    // Changes
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct GetChangesQuery {
+        /// Token returned from the previous sync. Takes priority over `since`.
+        pub token: Option<SyncToken>,
+
+        /// Only return items changed after this point. Used for the first sync.
+        #[serde(
+            default,
+            serialize_with = "serialize_datetime_opt",
+            deserialize_with = "deserialize_datetime_opt"
+        )]
+        pub since: Option<DateTime<Utc>>,
+
+        pub limit: Option<usize>,
+
+        pub server_id: String,
+    }
+
+    impl GetChangesQuery {
+        /// Where the server should resume from for each table. `None` means from the beginning.
+        ///
+        /// Errors if the token can't be read so the client isn't quietly sent everything again.
+        pub fn cursor(&self) -> CrateResult<SyncCursor> {
+            if let Some(token) = self.token.as_ref().filter(|v| !v.is_empty()) {
+                return token
+                    .cursor()
+                    .ok_or_else(|| Error::InvalidSyncToken(token.to_string()));
+            }
+
+            let position = self
+                .since
+                .map(|updated_at| SyncPosition { updated_at, id: 0 });
+
+            Ok(SyncCursor {
+                books: position,
+                authors: position,
+            })
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct PublicChanges {
+        pub books: Vec<PublicBook>,
+        pub authors: Vec<PublicAuthor>,
+
+        pub deleted: Vec<Tombstone>,
+
+        /// Send back in the next [`GetChangesQuery`] to continue where this left off.
+        pub next_token: SyncToken,
+        /// There are more changes waiting. Request again with `next_token`.
+        pub has_more: bool,
+    }
+
+    impl PublicChanges {
+        /// Splits the changed items into updates and tombstones, and creates the token for the next sync.
+        ///
+        /// Each table is expected to be ordered by [`SyncPosition`] and paged on its own, so each keeps its own
+        /// position. A table which didn't change keeps its position from `previous`.
+        pub fn new(
+            books: Vec<PublicBook>,
+            authors: Vec<PublicAuthor>,
+            previous: SyncCursor,
+            has_more: bool,
+        ) -> Self {
+            let mut cursor = previous;
+            let mut deleted = Vec::new();
+
+            let books = books
+                .into_iter()
+                .filter_map(|book| {
+                    cursor.update(ChangeType::Book, book.updated_at, book.id);
+
+                    if let Some(deleted_at) = book.deleted_at {
+                        deleted.push(Tombstone {
+                            id: book.id,
+                            type_of: ChangeType::Book,
+                            deleted_at,
+                        });
+
+                        None
+                    } else {
+                        Some(book)
+                    }
+                })
+                .collect();
+
+            let authors = authors
+                .into_iter()
+                .filter_map(|author| {
+                    cursor.update(ChangeType::Author, author.updated_at, author.id);
+
+                    if let Some(deleted_at) = author.deleted_at {
+                        deleted.push(Tombstone {
+                            id: author.id,
+                            type_of: ChangeType::Author,
+                            deleted_at,
+                        });
+
+                        None
+                    } else {
+                        Some(author)
+                    }
+                })
+                .collect();
+
+            Self {
+                books,
+                authors,
+                deleted,
+                next_token: SyncToken::from(cursor),
+                has_more,
+            }
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.books.is_empty() && self.authors.is_empty() && self.deleted.is_empty()
+        }
+
+        /// Applies the changes to a locally mirrored set of books and authors.
+        ///
+        /// Items are only replaced if the incoming one isn't older than what we already have.
+        pub fn apply_to(
+            self,
+            books: &mut HashMap<usize, PublicBook>,
+            authors: &mut HashMap<usize, PublicAuthor>,
+        ) {
+            for book in self.books {
+                if books
+                    .get(&book.id)
+                    .map(|v| v.updated_at <= book.updated_at)
+                    .unwrap_or(true)
+                {
+                    books.insert(book.id, book);
+                }
+            }
+
+            for author in self.authors {
+                if authors
+                    .get(&author.id)
+                    .map(|v| v.updated_at <= author.updated_at)
+                    .unwrap_or(true)
+                {
+                    authors.insert(author.id, author);
+                }
+            }
+
+            for tombstone in self.deleted {
+                match tombstone.type_of {
+                    ChangeType::Book => {
+                        books.remove(&tombstone.id);
+                    }
+
+                    ChangeType::Author => {
+                        authors.remove(&tombstone.id);
+                    }
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+    pub struct Tombstone {
+        pub id: usize,
+        pub type_of: ChangeType,
+
+        #[serde(
+            serialize_with = "serialize_datetime",
+            deserialize_with = "deserialize_datetime"
+        )]
+        pub deleted_at: DateTime<Utc>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ChangeType {
+        Book,
+        Author,
+    }
+
+    /// The last change a client has seen. Ordered by time, then by id.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct SyncPosition {
+        pub updated_at: DateTime<Utc>,
+        pub id: usize,
+    }
+
+    /// The last change a client has seen of each table.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct SyncCursor {
+        pub books: Option<SyncPosition>,
+        pub authors: Option<SyncPosition>,
+    }
+
+    impl SyncCursor {
+        pub fn get(&self, type_of: ChangeType) -> Option<SyncPosition> {
+            match type_of {
+                ChangeType::Book => self.books,
+                ChangeType::Author => self.authors,
+            }
+        }
+
+        /// Moves the table's position forward. Never moves it back.
+        pub fn update(&mut self, type_of: ChangeType, updated_at: DateTime<Utc>, id: usize) {
+            let position = match type_of {
+                ChangeType::Book => &mut self.books,
+                ChangeType::Author => &mut self.authors,
+            };
+
+            let value = SyncPosition { updated_at, id };
+
+            if position.map(|v| v < value).unwrap_or(true) {
+                *position = Some(value);
+            }
+        }
+    }
+
+    /// Opaque to the client. Only the server should be reading it.
+    ///
+    /// Formatted as `{books}_{authors}` where each is `{millis}.{id}`, or empty if the table wasn't seen yet.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+    pub struct SyncToken(String);
+
+    impl SyncToken {
+        pub fn cursor(&self) -> Option<SyncCursor> {
+            let (books, authors) = self.0.split_once('_')?;
+
+            Some(SyncCursor {
+                books: Self::parse_position(books)?,
+                authors: Self::parse_position(authors)?,
+            })
+        }
+
+        /// `None` if invalid. `Some(None)` if empty.
+        fn parse_position(value: &str) -> Option<Option<SyncPosition>> {
+            if value.is_empty() {
+                return Some(None);
+            }
+
+            // Millis are negative before 1970 so '-' can't be used as the separator.
+            let (time, id) = value.split_once('.')?;
+
+            Some(Some(SyncPosition {
+                updated_at: Utc.timestamp_millis_opt(time.parse().ok()?).single()?,
+                id: id.parse().ok()?,
+            }))
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+    }
+
+    impl From<SyncCursor> for SyncToken {
+        fn from(value: SyncCursor) -> Self {
+            if value == SyncCursor::default() {
+                return Self::default();
+            }
+
+            let format = |position: Option<SyncPosition>| {
+                position
+                    .map(|v| format!("{}.{}", v.updated_at.timestamp_millis(), v.id))
+                    .unwrap_or_default()
+            };
+
+            Self(format!("{}_{}", format(value.books), format(value.authors)))
+        }
+    }
+
+    impl fmt::Display for SyncToken {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl FromStr for SyncToken {
+        type Err = crate::error::Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self(s.to_string()))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SyncToken {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(Self(String::deserialize(deserializer)?))
+        }
+    }
+
+    impl Serialize for SyncToken {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&self.0)
+        }
+    }
+}
+
+mod patch {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+
+    use crate::util::{apply_required, ApplyPatch, Patch};
+
+    use super::{PublicAuthor, PublicBook};
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+    pub struct BookPatch {
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub title: Patch<String>,
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub clean_title: Patch<String>,
+
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub description: Patch<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub rating: Option<f64>,
+
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub thumb_url: Patch<String>,
+
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub isbns: Patch<Vec<String>>,
+
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub display_author_id: Patch<usize>,
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub publisher: Patch<String>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub author_ids: Option<Vec<usize>>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub is_public: Option<bool>,
+
+        #[serde(
+            default,
+            skip_serializing_if = "Patch::is_missing",
+            with = "crate::util::patch::naivedate"
+        )]
+        pub available_at: Patch<NaiveDate>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub language: Option<u16>,
+    }
+
+    impl ApplyPatch for PublicBook {
+        type Patch = BookPatch;
+
+        fn apply_patch(&mut self, patch: Self::Patch) -> bool {
+            let mut changed = patch.title.apply(&mut self.title);
+            changed |= patch.clean_title.apply(&mut self.clean_title);
+            changed |= patch.description.apply(&mut self.description);
+            changed |= apply_required(patch.rating, &mut self.rating);
+            changed |= patch.thumb_url.apply(&mut self.thumb_url);
+            changed |= patch.isbns.apply(&mut self.isbns);
+            changed |= patch.display_author_id.apply(&mut self.display_author_id);
+            changed |= patch.publisher.apply(&mut self.publisher);
+            changed |= apply_required(patch.author_ids, &mut self.author_ids);
+            changed |= apply_required(patch.is_public, &mut self.is_public);
+            changed |= patch.available_at.apply(&mut self.available_at);
+            changed |= apply_required(patch.language, &mut self.language);
+
+            changed
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+    pub struct AuthorPatch {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub description: Patch<String>,
+        #[serde(
+            default,
+            skip_serializing_if = "Patch::is_missing",
+            with = "crate::util::patch::naivedate"
+        )]
+        pub birth_date: Patch<NaiveDate>,
+
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        pub thumb_url: Patch<String>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub other_names: Option<Vec<String>>,
+    }
+
+    impl ApplyPatch for PublicAuthor {
+        type Patch = AuthorPatch;
+
+        fn apply_patch(&mut self, patch: Self::Patch) -> bool {
+            let mut changed = apply_required(patch.name, &mut self.name);
+            changed |= patch.description.apply(&mut self.description);
+            changed |= patch.birth_date.apply(&mut self.birth_date);
+            changed |= patch.thumb_url.apply(&mut self.thumb_url);
+            changed |= apply_required(patch.other_names, &mut self.other_names);
+
+            changed
+        }
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Serialize_repr, Deserialize_repr};
 
 pub mod librarian;
+pub mod opds;
 pub mod reader;
 
 // List Response
@@ -97,4 +98,13 @@ pub enum ErrorCodeResponse {
     /// Only set for client errors.
     Client,
     NotLoggedIn,
+
+    /// Something went wrong on the server. Details are only logged.
+    Internal,
+    /// Unable to parse the provided data.
+    InvalidData,
+    InvalidSource,
+    InvalidId,
+    InvalidIsbn,
+    InvalidImage,
 }
\ No newline at end of file
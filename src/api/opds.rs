@@ -0,0 +1,612 @@
+//! OPDS catalog feeds for e-reader apps.
+//!
+//! [`OpdsFeed`] writes OPDS 1.2 (Atom XML) and OPDS 2.0 (JSON) versions of the same feed.
+//! URLs are created by the server through [`OpdsUrls`].
+
+use std::fmt::Write;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::{
+    api::{
+        librarian::{PartialBook, PublicAuthor, PublicBook},
+        QueryListResponse,
+    },
+    get_language_code, ThumbnailStore,
+};
+
+pub static OPDS_ACQUISITION_TYPE: &str =
+    "application/atom+xml;profile=opds-catalog;kind=acquisition";
+pub static OPDS_NAVIGATION_TYPE: &str = "application/atom+xml;profile=opds-catalog;kind=navigation";
+pub static OPDS_2_TYPE: &str = "application/opds+json";
+pub static OPDS_2_PUBLICATION_TYPE: &str = "application/opds-publication+json";
+pub static OPENSEARCH_TYPE: &str = "application/opensearchdescription+xml";
+
+pub static REL_ACQUISITION: &str = "http://opds-spec.org/acquisition";
+pub static REL_IMAGE: &str = "http://opds-spec.org/image";
+pub static REL_THUMBNAIL: &str = "http://opds-spec.org/image/thumbnail";
+
+/// Creates the URLs used inside of the feeds.
+pub trait OpdsUrls {
+    /// Page of the book.
+    fn book(&self, id: usize) -> String;
+
+    /// Acquisition feed of the author's books.
+    fn author_books(&self, id: usize) -> String;
+
+    /// `None` if the book or author doesn't have an image.
+    fn thumbnail(&self, value: &ThumbnailStore) -> Option<String>;
+
+    /// Links to download the book. `rel` should be [`REL_ACQUISITION`] or one of its sub-types.
+    ///
+    /// Books without one are left out of acquisition feeds since clients can't do anything with them.
+    fn acquisitions(&self, book_id: usize) -> Vec<OpdsLink>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpdsLink {
+    pub rel: String,
+    pub href: String,
+    pub type_of: Option<String>,
+    pub title: Option<String>,
+}
+
+impl OpdsLink {
+    pub fn new(rel: impl Into<String>, href: impl Into<String>) -> Self {
+        Self {
+            rel: rel.into(),
+            href: href.into(),
+            type_of: None,
+            title: None,
+        }
+    }
+
+    pub fn with_type(mut self, value: impl Into<String>) -> Self {
+        self.type_of = Some(value.into());
+        self
+    }
+
+    pub fn with_title(mut self, value: impl Into<String>) -> Self {
+        self.title = Some(value.into());
+        self
+    }
+
+    /// `rel` is [`REL_ACQUISITION`] or one of its sub-types. eg. `http://opds-spec.org/acquisition/open-access`
+    pub fn is_acquisition(&self) -> bool {
+        self.rel
+            .strip_prefix(REL_ACQUISITION)
+            .is_some_and(|v| v.is_empty() || v.starts_with('/'))
+    }
+
+    fn write_xml(&self, out: &mut String) {
+        let _ = write!(
+            out,
+            r#"<link rel="{}" href="{}""#,
+            escape_xml(&self.rel),
+            escape_xml(&self.href)
+        );
+
+        if let Some(type_of) = &self.type_of {
+            let _ = write!(out, r#" type="{}""#, escape_xml(type_of));
+        }
+
+        if let Some(title) = &self.title {
+            let _ = write!(out, r#" title="{}""#, escape_xml(title));
+        }
+
+        out.push_str("/>");
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = json!({
+            "rel": self.rel,
+            "href": self.href,
+        });
+
+        if let Some(type_of) = &self.type_of {
+            value["type"] = json!(type_of);
+        }
+
+        if let Some(title) = &self.title {
+            value["title"] = json!(title);
+        }
+
+        value
+    }
+}
+
+/// Position of the feed's items in the full list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpdsPagination {
+    pub offset: usize,
+    pub limit: usize,
+    pub total: usize,
+}
+
+impl OpdsPagination {
+    pub fn previous_offset(self) -> Option<usize> {
+        (self.offset != 0).then(|| self.offset.saturating_sub(self.limit))
+    }
+
+    pub fn next_offset(self) -> Option<usize> {
+        let next = self.offset + self.limit;
+        (self.limit != 0 && next < self.total).then_some(next)
+    }
+
+    pub fn last_offset(self) -> usize {
+        if self.limit == 0 || self.total == 0 {
+            0
+        } else {
+            (self.total - 1) / self.limit * self.limit
+        }
+    }
+
+    /// Starts at 1.
+    pub fn current_page(self) -> usize {
+        self.offset.checked_div(self.limit).unwrap_or_default() + 1
+    }
+}
+
+impl<V> From<&QueryListResponse<V>> for OpdsPagination {
+    fn from(value: &QueryListResponse<V>) -> Self {
+        Self {
+            offset: value.offset,
+            limit: value.limit,
+            total: value.total,
+        }
+    }
+}
+
+/// A book entry in an acquisition feed. Created from a [`PartialBook`] or [`PublicBook`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpdsBook<'a> {
+    pub id: usize,
+
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub thumb_url: ThumbnailStore,
+    pub isbns: &'a [String],
+    pub publisher: Option<&'a str>,
+    pub available_at: Option<NaiveDate>,
+    pub language: u16,
+    /// Uses the feed's `updated` if `None`.
+    pub updated_at: Option<DateTime<Utc>>,
+
+    pub authors: Vec<&'a PublicAuthor>,
+}
+
+impl<'a> OpdsBook<'a> {
+    /// Adds the authors whose ids are in `author_ids`, in that order.
+    pub fn with_authors(mut self, author_ids: &[usize], authors: &'a [PublicAuthor]) -> Self {
+        self.authors = author_ids
+            .iter()
+            .filter_map(|id| authors.iter().find(|v| v.id == *id))
+            .collect();
+
+        self
+    }
+
+    /// `None` if there's nothing to acquire.
+    fn acquisitions(&self, urls: &impl OpdsUrls) -> Option<Vec<OpdsLink>> {
+        let links = urls.acquisitions(self.id);
+
+        if links.iter().any(OpdsLink::is_acquisition) {
+            Some(links)
+        } else {
+            None
+        }
+    }
+
+    fn links(&self, urls: &impl OpdsUrls, acquisitions: Vec<OpdsLink>) -> Vec<OpdsLink> {
+        let mut links = vec![OpdsLink::new("alternate", urls.book(self.id)).with_type("text/html")];
+
+        if let Some(thumb) = urls.thumbnail(&self.thumb_url) {
+            links.push(OpdsLink::new(REL_IMAGE, thumb.clone()));
+            links.push(OpdsLink::new(REL_THUMBNAIL, thumb));
+        }
+
+        links.extend(acquisitions);
+
+        links
+    }
+
+    /// `urn:isbn:` if the book has one, otherwise the book's URL.
+    fn identifier(&self, urls: &impl OpdsUrls) -> String {
+        self.isbns
+            .first()
+            .map(|v| format!("urn:isbn:{v}"))
+            .unwrap_or_else(|| urls.book(self.id))
+    }
+}
+
+impl<'a> From<&'a PartialBook> for OpdsBook<'a> {
+    fn from(value: &'a PartialBook) -> Self {
+        Self {
+            id: value.id,
+            title: value.title.as_deref(),
+            description: value.description.as_deref(),
+            thumb_url: ThumbnailStore::from(value.thumb_url.as_deref()),
+            isbns: value.isbns.as_deref().unwrap_or_default(),
+            publisher: None,
+            available_at: value.available_at,
+            language: value.language,
+            updated_at: None,
+            authors: Vec::new(),
+        }
+    }
+}
+
+impl<'a> From<&'a PublicBook> for OpdsBook<'a> {
+    fn from(value: &'a PublicBook) -> Self {
+        Self {
+            id: value.id,
+            title: value.title.as_deref(),
+            description: value.description.as_deref(),
+            thumb_url: ThumbnailStore::from(value.thumb_url.as_deref()),
+            isbns: value.isbns.as_deref().unwrap_or_default(),
+            publisher: value.publisher.as_deref(),
+            available_at: value.available_at,
+            language: value.language,
+            updated_at: Some(value.updated_at),
+            authors: Vec::new(),
+        }
+    }
+}
+
+/// Feed level information. The items are passed in when writing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpdsFeed {
+    /// Unique and permanent. eg. "urn:uuid:.." or the URL of the feed.
+    pub id: String,
+    pub title: String,
+    pub updated: DateTime<Utc>,
+
+    /// URL of this feed without the `offset` and `limit` parameters.
+    pub url: String,
+    /// URL of the root navigation feed.
+    pub start_url: Option<String>,
+    /// URL of the OpenSearch description document.
+    pub search_url: Option<String>,
+}
+
+impl OpdsFeed {
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        url: impl Into<String>,
+        updated: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            updated,
+            url: url.into(),
+            start_url: None,
+            search_url: None,
+        }
+    }
+
+    /// OPDS 1.2 acquisition feed of books. Books without an acquisition link are skipped.
+    pub fn acquisition_xml(
+        &self,
+        pagination: Option<OpdsPagination>,
+        books: &[OpdsBook],
+        urls: &impl OpdsUrls,
+    ) -> String {
+        let mut out = self.start_xml(OPDS_ACQUISITION_TYPE, pagination);
+
+        for book in books {
+            let Some(acquisitions) = book.acquisitions(urls) else {
+                continue;
+            };
+
+            let updated = book.updated_at.unwrap_or(self.updated);
+
+            out.push_str("<entry>");
+            write_element(&mut out, "title", book.title.unwrap_or_default());
+            write_element(&mut out, "id", &book.identifier(urls));
+            write_element(&mut out, "updated", &format_datetime(updated));
+
+            for author in &book.authors {
+                out.push_str("<author>");
+                write_element(&mut out, "name", &author.name);
+                write_element(&mut out, "uri", &urls.author_books(author.id));
+                out.push_str("</author>");
+            }
+
+            if let Some(publisher) = book.publisher {
+                write_element(&mut out, "dc:publisher", publisher);
+            }
+
+            if let Some(date) = book.available_at {
+                write_element(&mut out, "dc:issued", &date.to_string());
+            }
+
+            write_element(&mut out, "dc:language", get_language_code(book.language));
+
+            for isbn in book.isbns {
+                write_element(&mut out, "dc:identifier", &format!("urn:isbn:{isbn}"));
+            }
+
+            if let Some(description) = book.description {
+                write_element(&mut out, "summary", description);
+            }
+
+            for link in book.links(urls, acquisitions) {
+                link.write_xml(&mut out);
+            }
+
+            out.push_str("</entry>");
+        }
+
+        out.push_str("</feed>");
+
+        out
+    }
+
+    /// OPDS 1.2 navigation feed which links each author to their books.
+    pub fn navigation_xml(
+        &self,
+        pagination: Option<OpdsPagination>,
+        authors: &[PublicAuthor],
+        urls: &impl OpdsUrls,
+    ) -> String {
+        let mut out = self.start_xml(OPDS_NAVIGATION_TYPE, pagination);
+
+        for author in authors {
+            let href = urls.author_books(author.id);
+
+            out.push_str("<entry>");
+            write_element(&mut out, "title", &author.name);
+            write_element(&mut out, "id", &href);
+            write_element(&mut out, "updated", &format_datetime(author.updated_at));
+
+            if let Some(description) = &author.description {
+                write_element(&mut out, "content", description);
+            }
+
+            OpdsLink::new("subsection", href)
+                .with_type(OPDS_ACQUISITION_TYPE)
+                .write_xml(&mut out);
+
+            if let Some(thumb) = urls.thumbnail(&ThumbnailStore::from(author.thumb_url.as_deref()))
+            {
+                OpdsLink::new(REL_THUMBNAIL, thumb).write_xml(&mut out);
+            }
+
+            out.push_str("</entry>");
+        }
+
+        out.push_str("</feed>");
+
+        out
+    }
+
+    /// OPDS 2.0 feed of publications. Books without an acquisition link are skipped.
+    pub fn acquisition_json(
+        &self,
+        pagination: Option<OpdsPagination>,
+        books: &[OpdsBook],
+        urls: &impl OpdsUrls,
+    ) -> Value {
+        let publications = books
+            .iter()
+            .filter_map(|book| {
+                let acquisitions = book.acquisitions(urls)?;
+
+                let mut metadata = json!({
+                    "@type": "http://schema.org/Book",
+                    "title": book.title.unwrap_or_default(),
+                    "identifier": book.identifier(urls),
+                    "modified": format_datetime(book.updated_at.unwrap_or(self.updated)),
+                    "language": get_language_code(book.language),
+                });
+
+                if !book.authors.is_empty() {
+                    metadata["author"] = book
+                        .authors
+                        .iter()
+                        .map(|v| {
+                            json!({
+                                "name": v.name,
+                                "links": [{ "href": urls.author_books(v.id), "type": OPDS_2_TYPE }],
+                            })
+                        })
+                        .collect();
+                }
+
+                if let Some(publisher) = book.publisher {
+                    metadata["publisher"] = json!(publisher);
+                }
+
+                if let Some(date) = book.available_at {
+                    metadata["published"] = json!(date.to_string());
+                }
+
+                if let Some(description) = book.description {
+                    metadata["description"] = json!(description);
+                }
+
+                let mut links = vec![OpdsLink::new("self", urls.book(book.id))
+                    .with_type(OPDS_2_PUBLICATION_TYPE)
+                    .to_json()];
+
+                links.extend(acquisitions.iter().map(OpdsLink::to_json));
+
+                let images = urls
+                    .thumbnail(&book.thumb_url)
+                    .map(|href| vec![json!({ "href": href })])
+                    .unwrap_or_default();
+
+                Some(json!({
+                    "metadata": metadata,
+                    "links": links,
+                    "images": images,
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "metadata": self.json_metadata(pagination),
+            "links": self.json_links(pagination),
+            "publications": publications,
+        })
+    }
+
+    /// OPDS 2.0 navigation feed which links each author to their books.
+    pub fn navigation_json(
+        &self,
+        pagination: Option<OpdsPagination>,
+        authors: &[PublicAuthor],
+        urls: &impl OpdsUrls,
+    ) -> Value {
+        let navigation = authors
+            .iter()
+            .map(|v| {
+                json!({
+                    "href": urls.author_books(v.id),
+                    "title": v.name,
+                    "type": OPDS_2_TYPE,
+                    "rel": "subsection",
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "metadata": self.json_metadata(pagination),
+            "links": self.json_links(pagination),
+            "navigation": navigation,
+        })
+    }
+
+    /// Self, start, search and pagination links.
+    fn links(&self, type_of: &str, pagination: Option<OpdsPagination>) -> Vec<OpdsLink> {
+        let mut links = Vec::new();
+
+        let self_url = match pagination {
+            Some(page) => self.page_url(page.offset, page.limit),
+            None => self.url.clone(),
+        };
+
+        links.push(OpdsLink::new("self", self_url).with_type(type_of));
+
+        if let Some(url) = &self.start_url {
+            let start_type = if type_of == OPDS_2_TYPE {
+                OPDS_2_TYPE
+            } else {
+                OPDS_NAVIGATION_TYPE
+            };
+
+            links.push(OpdsLink::new("start", url.clone()).with_type(start_type));
+        }
+
+        if let Some(url) = &self.search_url {
+            links.push(OpdsLink::new("search", url.clone()).with_type(OPENSEARCH_TYPE));
+        }
+
+        if let Some(page) = pagination {
+            links.push(OpdsLink::new("first", self.page_url(0, page.limit)).with_type(type_of));
+
+            if let Some(offset) = page.previous_offset() {
+                links.push(
+                    OpdsLink::new("previous", self.page_url(offset, page.limit)).with_type(type_of),
+                );
+            }
+
+            if let Some(offset) = page.next_offset() {
+                links.push(
+                    OpdsLink::new("next", self.page_url(offset, page.limit)).with_type(type_of),
+                );
+            }
+
+            links.push(
+                OpdsLink::new("last", self.page_url(page.last_offset(), page.limit))
+                    .with_type(type_of),
+            );
+        }
+
+        links
+    }
+
+    fn page_url(&self, offset: usize, limit: usize) -> String {
+        let separator = if self.url.contains('?') { '&' } else { '?' };
+
+        format!("{}{separator}offset={offset}&limit={limit}", self.url)
+    }
+
+    fn start_xml(&self, type_of: &str, pagination: Option<OpdsPagination>) -> String {
+        let mut out = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/terms/" xmlns:opds="http://opds-spec.org/2010/catalog" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">"#,
+        );
+
+        write_element(&mut out, "id", &self.id);
+        write_element(&mut out, "title", &self.title);
+        write_element(&mut out, "updated", &format_datetime(self.updated));
+
+        if let Some(page) = pagination {
+            write_element(&mut out, "opensearch:totalResults", &page.total.to_string());
+            write_element(
+                &mut out,
+                "opensearch:startIndex",
+                &(page.offset + 1).to_string(),
+            );
+            write_element(&mut out, "opensearch:itemsPerPage", &page.limit.to_string());
+        }
+
+        for link in self.links(type_of, pagination) {
+            link.write_xml(&mut out);
+        }
+
+        out
+    }
+
+    fn json_metadata(&self, pagination: Option<OpdsPagination>) -> Value {
+        let mut metadata = json!({
+            "title": self.title,
+            "modified": format_datetime(self.updated),
+        });
+
+        if let Some(page) = pagination {
+            metadata["numberOfItems"] = json!(page.total);
+            metadata["itemsPerPage"] = json!(page.limit);
+            metadata["currentPage"] = json!(page.current_page());
+        }
+
+        metadata
+    }
+
+    fn json_links(&self, pagination: Option<OpdsPagination>) -> Value {
+        self.links(OPDS_2_TYPE, pagination)
+            .iter()
+            .map(OpdsLink::to_json)
+            .collect()
+    }
+}
+
+fn format_datetime(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn write_element(out: &mut String, name: &str, value: &str) {
+    let _ = write!(out, "<{name}>{}</{name}>", escape_xml(value));
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => (),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -6,6 +6,7 @@ pub mod infinite_scroll;
 pub mod multi_select;
 pub mod popup;
 pub mod select;
+pub mod toast;
 pub mod upload;
 
 pub use carousel::CarouselComponent;
@@ -16,5 +17,13 @@ pub use filter::{
 };
 pub use infinite_scroll::{InfiniteScroll, InfiniteScrollEvent};
 pub use multi_select::{MultiSelectEvent, MultiSelectItem, MultiSelectModule, MultiSelectNewItem};
-pub use popup::{Popup, PopupClose, PopupType};
+pub use popup::{
+    dialog::{
+        use_confirm, AlertDialog, ConfirmDialog, ConfirmOptions, DialogProvider, PromptDialog,
+        UseConfirmHandle,
+    },
+    stack::{use_popup_stack, PopupHandle, PopupStack, PopupStackProvider},
+    Popup, PopupClose, PopupType,
+};
+pub use toast::{use_toast, Toast, ToastId, ToastLevel, ToastProvider, UseToastHandle};
 pub use upload::UploadModule;
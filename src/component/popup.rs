@@ -1,18 +1,40 @@
-use std::{cell::Cell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
-use gloo_utils::body;
+use gloo_utils::{body, document, window};
 use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
-use web_sys::{Element, HtmlElement, MouseEvent};
+use web_sys::{Element, HtmlElement, KeyboardEvent, MouseEvent};
 use yew::prelude::*;
 
-use crate::util::{does_parent_contain_attribute, does_parent_contain_class};
+use crate::util::{
+    does_parent_contain_attribute, does_parent_contain_class, get_focusable_elements,
+};
 
 pub mod button;
 pub mod compare;
+pub mod dialog;
+pub mod position;
 pub mod search;
+pub mod stack;
+
+use position::{position_at_point, RepositionObserver};
+use stack::{PopupHandle, PopupStack};
 
 static YEW_CLOSE_POPUP: &str = "yew_close_popup";
 
+thread_local! {
+    /// Number of open popups which are currently locking the body from scrolling.
+    static SCROLL_LOCKS: Cell<usize> = const { Cell::new(0) };
+    /// Inline `overflow` of the body from before the first lock. Restored once the last lock is removed.
+    static BODY_OVERFLOW: RefCell<String> = const { RefCell::new(String::new()) };
+
+    /// Popups outside of a [`stack::PopupStackProvider`] in the order they were opened. Only the last one handles keys.
+    static OPEN_POPUPS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    static NEXT_POPUP_ID: Cell<usize> = const { Cell::new(0) };
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum PopupType {
     /// Full foreground overlay
@@ -52,30 +74,122 @@ pub struct Property {
     pub type_of: PopupType,
 
     pub on_close: Callback<()>,
+
+    /// Accessible name of the dialog. Use `aria_labelledby` instead if it has a visible title.
+    #[prop_or_default]
+    pub aria_label: Option<AttrValue>,
+    /// Id of the element containing the dialog's title.
+    #[prop_or_default]
+    pub aria_labelledby: Option<AttrValue>,
+
+    /// Called once with our handle if we're inside of a [`stack::PopupStackProvider`].
+    #[prop_or_default]
+    pub on_open: Option<Callback<PopupHandle>>,
 }
 
 pub struct Popup {
     node_ref: NodeRef,
     #[allow(clippy::type_complexity)]
     closure: Rc<Cell<Option<Closure<dyn FnMut(MouseEvent)>>>>,
+    key_closure: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+
+    /// Element which had focus before we opened. Focused again once we close.
+    opener: Option<HtmlElement>,
+    scroll_lock: Option<ScrollLock>,
+    /// Created once for the point we're opened at. Replaced if the point changes.
+    position_observer: Option<((i32, i32), RepositionObserver)>,
+
+    /// Identifies us in [`OPEN_POPUPS`] when we're not in a stack.
+    id: usize,
+    /// Clicks from before this time opened us and shouldn't close us.
+    opened_at: f64,
+    /// Registered with the stack which then handles clicks and keys for us.
+    stack: Option<(PopupStack, PopupHandle)>,
+    /// Re-renders us when the stack changes so our z-index stays current.
+    _stack_listener: Option<ContextHandle<PopupStack>>,
 }
 
 impl Component for Popup {
     type Message = ();
     type Properties = Property;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut this = Self {
             node_ref: NodeRef::default(),
             closure: Rc::new(Cell::default()),
+            key_closure: None,
+            opener: document()
+                .active_element()
+                .and_then(|v| v.dyn_into::<HtmlElement>().ok()),
+            scroll_lock: None,
+            position_observer: None,
+
+            id: NEXT_POPUP_ID.with(|v| v.replace(v.get() + 1)),
+            opened_at: now(),
+            stack: None,
+            _stack_listener: None,
+        };
+
+        if let Some((stack, listener)) = ctx
+            .link()
+            .context::<PopupStack>(ctx.link().callback(|_| ()))
+        {
+            let handle = stack.push(
+                ctx.props().type_of,
+                this.node_ref.clone(),
+                ctx.props().on_close.clone(),
+                this.opened_at,
+            );
+
+            if let Some(cb) = ctx.props().on_open.as_ref() {
+                cb.emit(handle);
+            }
+
+            this.stack = Some((stack, handle));
+            this._stack_listener = Some(listener);
+        } else {
+            OPEN_POPUPS.with(|v| v.borrow_mut().push(this.id));
+        }
+
+        this.update_scroll_lock(ctx.props().type_of);
+
+        this
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.update_scroll_lock(ctx.props().type_of);
+
+        if let Some((stack, handle)) = self.stack.as_ref() {
+            stack.update(*handle, ctx.props().type_of, ctx.props().on_close.clone());
         }
+
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let z_index = self
+            .stack
+            .as_ref()
+            .map(|(stack, handle)| format!(" z-index: {};", stack.z_index(*handle)))
+            .unwrap_or_default();
+
         match ctx.props().type_of {
             PopupType::FullOverlay => {
                 let display = html! {
-                    <div ref={ self.node_ref.clone() } class="modal fade show" tabindex="-1" style="display: block;">
+                    <div
+                        ref={ self.node_ref.clone() }
+                        class="modal fade show"
+                        tabindex="-1"
+                        style={ format!("display: block;{z_index}") }
+                        role="dialog"
+                        aria-modal="true"
+                        aria-label={ ctx.props().aria_label.clone() }
+                        aria-labelledby={ ctx.props().aria_labelledby.clone() }
+                    >
                         <div class={ classes!("modal-dialog", "modal-dialog-centered", ctx.props().classes.clone()) }>
                             <div class="modal-content text-bg-dark">
                                 { for ctx.props().children.iter() }
@@ -88,10 +202,18 @@ impl Component for Popup {
             }
 
             PopupType::AtPoint(pos_x, pos_y) => {
-                let styling = format!("left: {}px; top: {}px;", pos_x, pos_y);
+                let styling = format!("left: {}px; top: {}px;{z_index}", pos_x, pos_y);
 
                 let display = html! {
-                    <div ref={ self.node_ref.clone() } class={ classes!("popup-at-point", ctx.props().classes.clone()) } style={ styling }>
+                    <div
+                        ref={ self.node_ref.clone() }
+                        class={ classes!("popup-at-point", ctx.props().classes.clone()) }
+                        style={ styling }
+                        tabindex="-1"
+                        role="dialog"
+                        aria-label={ ctx.props().aria_label.clone() }
+                        aria-labelledby={ ctx.props().aria_labelledby.clone() }
+                    >
                         { for ctx.props().children.iter() }
                     </div>
                 };
@@ -101,7 +223,14 @@ impl Component for Popup {
 
             PopupType::Display => {
                 html! {
-                    <div ref={ self.node_ref.clone() } class={ classes!("popup-display", ctx.props().classes.clone()) }>
+                    <div
+                        ref={ self.node_ref.clone() }
+                        class={ classes!("popup-display", ctx.props().classes.clone()) }
+                        tabindex="-1"
+                        role="dialog"
+                        aria-label={ ctx.props().aria_label.clone() }
+                        aria-labelledby={ ctx.props().aria_labelledby.clone() }
+                    >
                         { for ctx.props().children.iter() }
                     </div>
                 }
@@ -109,9 +238,40 @@ impl Component for Popup {
         }
     }
 
-    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
-        // TODO: On render check dimensions of and adjust "AtPoint"
-        // TODO: Prevent parent onclick from calling this onclick from the same tick.
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        // Keep "AtPoint" on screen.
+        match (ctx.props().type_of, self.node_ref.cast::<HtmlElement>()) {
+            // Already watching. It repositions on resize and scroll by itself.
+            (PopupType::AtPoint(x, y), Some(_))
+                if self
+                    .position_observer
+                    .as_ref()
+                    .is_some_and(|(point, _)| *point == (x, y)) => {}
+
+            (PopupType::AtPoint(x, y), Some(container)) => {
+                let reposition = {
+                    let container = container.clone();
+                    move || position_at_point(&container, x as f64, y as f64)
+                };
+
+                reposition();
+
+                let observer = RepositionObserver::new(&[container.unchecked_ref()], reposition);
+
+                self.position_observer = Some(((x, y), observer));
+            }
+
+            _ => self.position_observer = None,
+        }
+
+        if first_render {
+            self.focus_inside();
+        }
+
+        // The stack routes clicks and keys to the topmost popup instead.
+        if self.stack.is_some() {
+            return;
+        }
 
         // FIX: rendered would be called again if we clicked an element containing another onclick event.
         // Resulted in our previous event being overwritten but not removed from the listener.
@@ -123,28 +283,214 @@ impl Component for Popup {
         let container = self.node_ref.clone();
         let viewing = ctx.props().type_of;
         let exit_fn = ctx.props().on_close.clone();
+        let opened_at = self.opened_at;
 
         let on_click = Closure::wrap(Box::new(move |event: MouseEvent| {
-            if let Some(target) = event.target() {
-                log::trace!("Check For Exit");
-
-                if viewing.should_exit(&container, target.unchecked_into()) {
-                    log::trace!("Emit Exit");
-                    exit_fn.emit(());
-                }
-            }
+            handle_outside_click(viewing, &container, opened_at, &exit_fn, &event);
         }) as Box<dyn FnMut(MouseEvent)>);
 
         let _ = body().add_event_listener_with_callback("click", on_click.as_ref().unchecked_ref());
 
         self.closure = Rc::new(Cell::new(Some(on_click)));
+
+        // Escape and Tab
+        if let Some(func) = self.key_closure.take() {
+            let _ = document()
+                .remove_event_listener_with_callback("keydown", func.as_ref().unchecked_ref());
+        }
+
+        let container = self.node_ref.clone();
+        let exit_fn = ctx.props().on_close.clone();
+        let id = self.id;
+
+        let on_keydown = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            // Otherwise Escape would close every open popup and their focus traps would fight.
+            // Already handled if the default was prevented. eg. the popup above us closed itself.
+            let is_topmost = OPEN_POPUPS.with(|v| v.borrow().last() == Some(&id));
+
+            if is_topmost && !event.default_prevented() {
+                handle_keydown(&container, &exit_fn, &event);
+            }
+        }) as Box<dyn FnMut(KeyboardEvent)>);
+
+        let _ = document()
+            .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref());
+
+        self.key_closure = Some(on_keydown);
     }
 
     fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some((stack, handle)) = self.stack.take() {
+            stack.remove(handle);
+        } else {
+            OPEN_POPUPS.with(|v| v.borrow_mut().retain(|v| *v != self.id));
+        }
+
         if let Some(func) = self.closure.take() {
             let _ =
                 body().remove_event_listener_with_callback("click", func.as_ref().unchecked_ref());
         }
+
+        if let Some(func) = self.key_closure.take() {
+            let _ = document()
+                .remove_event_listener_with_callback("keydown", func.as_ref().unchecked_ref());
+        }
+
+        // Give focus back to whatever opened us, as long as it's still on the page.
+        if let Some(opener) = self.opener.take() {
+            if opener.is_connected() {
+                let _ = opener.focus();
+            }
+        }
+    }
+}
+
+impl Popup {
+    /// Moves focus inside so keyboard users start in the popup.
+    fn focus_inside(&self) {
+        let Some(container) = self.node_ref.cast::<HtmlElement>() else {
+            return;
+        };
+
+        let active = document().active_element();
+
+        if container.contains(active.as_ref().map(|v| v.unchecked_ref())) {
+            return;
+        }
+
+        let autofocus = container
+            .query_selector("[autofocus]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok());
+
+        let _ = autofocus
+            .or_else(|| get_focusable_elements(&container).into_iter().next())
+            .unwrap_or(container)
+            .focus();
+    }
+
+    fn update_scroll_lock(&mut self, type_of: PopupType) {
+        let should_lock = type_of == PopupType::FullOverlay;
+
+        if should_lock != self.scroll_lock.is_some() {
+            self.scroll_lock = should_lock.then(ScrollLock::new);
+        }
+    }
+}
+
+/// Milliseconds since the page loaded. Same clock as `Event::time_stamp`.
+fn now() -> f64 {
+    window().performance().map(|v| v.now()).unwrap_or_default()
+}
+
+/// Closes the popup if the click was outside of it or on a [`PopupClose`].
+fn handle_outside_click(
+    type_of: PopupType,
+    container: &NodeRef,
+    opened_at: f64,
+    on_close: &Callback<()>,
+    event: &MouseEvent,
+) {
+    // The click which opened us is still bubbling up.
+    if event.time_stamp() <= opened_at || container.get().is_none() {
+        return;
+    }
+
+    if let Some(target) = event.target() {
+        log::trace!("Check For Exit");
+
+        if type_of.should_exit(container, target.unchecked_into()) {
+            log::trace!("Emit Exit");
+            on_close.emit(());
+        }
+    }
+}
+
+/// Escape closes the popup and Tab keeps focus inside of it.
+fn handle_keydown(container: &NodeRef, on_close: &Callback<()>, event: &KeyboardEvent) {
+    match event.key().as_str() {
+        "Escape" => {
+            event.prevent_default();
+            on_close.emit(());
+        }
+
+        "Tab" => {
+            if let Some(container) = container.cast::<HtmlElement>() {
+                trap_focus(&container, event);
+            }
+        }
+
+        _ => (),
+    }
+}
+
+/// Keeps Tab and Shift+Tab cycling through the container's focusable elements.
+fn trap_focus(container: &HtmlElement, event: &KeyboardEvent) {
+    let elements = get_focusable_elements(container);
+
+    let (Some(first), Some(last)) = (elements.first(), elements.last()) else {
+        // Nothing to move to. Keep focus on the container.
+        event.prevent_default();
+        let _ = container.focus();
+        return;
+    };
+
+    let active = document().active_element();
+    let is_inside = container.contains(active.as_ref().map(|v| v.unchecked_ref()));
+
+    let next = if event.shift_key() {
+        (!is_inside || active.as_ref() == Some(first.unchecked_ref())).then_some(last)
+    } else {
+        (!is_inside || active.as_ref() == Some(last.unchecked_ref())).then_some(first)
+    };
+
+    if let Some(next) = next {
+        event.prevent_default();
+        let _ = next.focus();
+    }
+}
+
+/// Stops the body from scrolling behind a [`PopupType::FullOverlay`]. Nested locks are counted.
+struct ScrollLock;
+
+impl ScrollLock {
+    fn new() -> Self {
+        SCROLL_LOCKS.with(|count| {
+            if count.get() == 0 {
+                let style = body().style();
+
+                BODY_OVERFLOW.with(|previous| {
+                    *previous.borrow_mut() =
+                        style.get_property_value("overflow").unwrap_or_default();
+                });
+
+                let _ = style.set_property("overflow", "hidden");
+            }
+
+            count.set(count.get() + 1);
+        });
+
+        Self
+    }
+}
+
+impl Drop for ScrollLock {
+    fn drop(&mut self) {
+        SCROLL_LOCKS.with(|count| {
+            count.set(count.get().saturating_sub(1));
+
+            if count.get() == 0 {
+                let style = body().style();
+                let previous = BODY_OVERFLOW.with(|v| v.take());
+
+                if previous.is_empty() {
+                    let _ = style.remove_property("overflow");
+                } else {
+                    let _ = style.set_property("overflow", &previous);
+                }
+            }
+        });
     }
 }
 
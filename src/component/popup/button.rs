@@ -1,10 +1,13 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
 use yew::{prelude::*, virtual_dom::AttrValue};
 
-use super::{Popup, PopupType};
+use super::{
+    position::{position_around, RepositionObserver},
+    Popup, PopupType,
+};
 
-// TODO: Update on Resize
-
-#[derive(Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ButtonPopupPosition {
     Top,
     #[default]
@@ -13,6 +16,36 @@ pub enum ButtonPopupPosition {
     Right,
 }
 
+impl ButtonPopupPosition {
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// The position a quarter turn clockwise. eg. `Top` -> `Right`
+    pub fn rotate(self) -> Self {
+        match self {
+            Self::Top => Self::Right,
+            Self::Right => Self::Bottom,
+            Self::Bottom => Self::Left,
+            Self::Left => Self::Top,
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Self::Top => "popup-top",
+            Self::Bottom => "popup-bottom",
+            Self::Left => "popup-left",
+            Self::Right => "popup-right",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq)]
 pub enum ButtonTitle {
     Text(AttrValue),
@@ -49,6 +82,10 @@ pub enum ButtonMsg {
 
 pub struct ButtonWithPopup {
     is_open: bool,
+
+    node_ref: NodeRef,
+    /// Created once the popup opens. Replaced if the popup element or position changes.
+    observer: Option<(ButtonPopupPosition, HtmlElement, RepositionObserver)>,
 }
 
 impl Component for ButtonWithPopup {
@@ -56,7 +93,12 @@ impl Component for ButtonWithPopup {
     type Properties = ButtonProperty;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self { is_open: false }
+        Self {
+            is_open: false,
+
+            node_ref: NodeRef::default(),
+            observer: None,
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -84,19 +126,16 @@ impl Component for ButtonWithPopup {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let popup_type = match ctx.props().position {
-            ButtonPopupPosition::Top => "popup-top",
-            ButtonPopupPosition::Bottom => "popup-bottom",
-            ButtonPopupPosition::Left => "popup-left",
-            ButtonPopupPosition::Right => "popup-right",
-        };
+        let popup_type = ctx.props().position.class();
 
         html! {
-            <div class={ classes!("button-popup-group", ctx.props().class.clone()) }>
+            <div ref={ self.node_ref.clone() } class={ classes!("button-popup-group", ctx.props().class.clone()) }>
                 {
                     match &ctx.props().title {
                         ButtonTitle::Text(text) => html! {
                             <button class="btn btn-secondary btn-sm" title="More Options"
+                                aria-haspopup="dialog"
+                                aria-expanded={ self.is_open.to_string() }
                                 onclick={ ctx.link().callback(|e: MouseEvent| {
                                     e.prevent_default();
                                     e.stop_propagation();
@@ -108,12 +147,25 @@ impl Component for ButtonWithPopup {
 
                         &ButtonTitle::Icon(text) => html! {
                             <span class="material-icons" title="More Options"
+                                role="button"
+                                tabindex="0"
+                                aria-haspopup="dialog"
+                                aria-expanded={ self.is_open.to_string() }
                                 onclick={ ctx.link().callback(|e: MouseEvent| {
                                     e.prevent_default();
                                     e.stop_propagation();
 
                                     ButtonMsg::TogglePopup
                                 }) }
+                                onkeydown={ ctx.link().batch_callback(|e: KeyboardEvent| {
+                                    // Act like a button for keyboard users.
+                                    if e.key() == "Enter" || e.key() == " " {
+                                        e.prevent_default();
+                                        Some(ButtonMsg::TogglePopup)
+                                    } else {
+                                        None
+                                    }
+                                }) }
                             >{ text }</span>
                         },
                     }
@@ -143,4 +195,55 @@ impl Component for ButtonWithPopup {
             </div>
         }
     }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if !self.is_open {
+            self.observer = None;
+            return;
+        }
+
+        let Some(group) = self.node_ref.cast::<Element>() else {
+            return;
+        };
+
+        let Some(popup) = group
+            .query_selector(".popup-display")
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+
+        let preferred = ctx.props().position;
+
+        // Already watching. It repositions on resize and scroll by itself.
+        if self
+            .observer
+            .as_ref()
+            .is_some_and(|(position, element, _)| *position == preferred && *element == popup)
+        {
+            return;
+        }
+
+        let reposition = {
+            let group = group.clone();
+            let popup = popup.clone();
+
+            move || {
+                let position = position_around(&group, &popup, preferred);
+
+                // Swap the class so the arrow and animations match the side we ended up on.
+                let classes = popup.class_list();
+                let _ = classes.remove_4("popup-top", "popup-bottom", "popup-left", "popup-right");
+                let _ = classes.add_1(position.class());
+            }
+        };
+
+        reposition();
+
+        let observer = RepositionObserver::new(&[&group, &popup], reposition);
+
+        self.observer = Some((preferred, popup, observer));
+    }
 }
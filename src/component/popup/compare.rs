@@ -1,20 +1,29 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, marker::PhantomData};
 
 use serde::Serialize;
 use yew::prelude::*;
 
+use crate::{Agent, Source};
+
 use super::{Popup, PopupType};
 
+pub use common_derive::Comparable;
+
+/// Used by the `Comparable` derive.
+#[doc(hidden)]
+pub use serde;
+#[doc(hidden)]
+pub use serde_json;
+
 type FieldName = &'static str;
 
-#[derive(Properties, PartialEq)]
-pub struct Property {
+#[derive(Properties)]
+pub struct Property<T: Comparable> {
     #[prop_or_default]
     pub classes: Classes,
 
     pub on_close: Callback<()>,
-    // TODO: Return the struct instead of HashMap.
-    pub on_submit: Callback<HashMap<FieldName, serde_json::Value>>,
+    pub on_submit: Callback<T>,
 
     pub compare: CompareContainer,
 
@@ -22,48 +31,67 @@ pub struct Property {
     pub show_equal_rows: bool,
 }
 
+impl<T: Comparable> PartialEq for Property<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.classes == other.classes
+            && self.on_close == other.on_close
+            && self.on_submit == other.on_submit
+            && self.compare == other.compare
+            && self.show_equal_rows == other.show_equal_rows
+    }
+}
+
 pub enum Msg {
     Ignore,
 
     OnClose,
     OnSubmit,
 
-    SetNewSelected(FieldName, SelectedSide),
+    SetNewSelected(FieldName, usize),
+    /// Select every field which has a value in the column.
+    SelectColumn(usize),
+    /// Tick or untick a single item of an array field.
+    ToggleItem(FieldName, usize, usize),
+    /// Take every item from every column.
+    MergeAll(FieldName),
 }
 
-pub struct PopupComparison {
-    selected: HashMap<FieldName, SelectedSide>,
+pub struct PopupComparison<T> {
+    selected: HashMap<FieldName, FieldSelection>,
+    /// Why the last save failed. Cleared once the selection changes.
+    error: Option<String>,
+
+    _type: PhantomData<T>,
 }
 
-impl Component for PopupComparison {
+impl<T: Comparable + 'static> Component for PopupComparison<T> {
     type Message = Msg;
-    type Properties = Property;
+    type Properties = Property<T>;
 
     fn create(ctx: &Context<Self>) -> Self {
         Self {
-            selected: ctx
-                .props()
-                .compare
-                .get_field_names()
-                .into_iter()
-                .map(|v| (v, SelectedSide::Left))
-                .collect(),
+            selected: Self::default_selection(&ctx.props().compare),
+            error: None,
+
+            _type: PhantomData,
         }
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _prev: &Property) -> bool {
-        self.selected = ctx
-            .props()
-            .compare
-            .get_field_names()
-            .into_iter()
-            .map(|v| (v, SelectedSide::Left))
-            .collect();
+    fn changed(&mut self, ctx: &Context<Self>, prev: &Property<T>) -> bool {
+        // Callbacks are often recreated by the parent on every render. Keep the selection unless the data changed.
+        if ctx.props().compare != prev.compare {
+            self.selected = Self::default_selection(&ctx.props().compare);
+            self.error = None;
+        }
 
         true
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        if !matches!(msg, Msg::Ignore | Msg::OnClose) {
+            self.error = None;
+        }
+
         match msg {
             Msg::Ignore => return false,
 
@@ -73,17 +101,60 @@ impl Component for PopupComparison {
 
                 let mut map = HashMap::new();
 
-                for (&field_name, &side) in &self.selected {
-                    if let Some(value) = compare.get_field_side_value(field_name, side) {
+                for (&field_name, selection) in &self.selected {
+                    if let Some(value) = compare.get_field_value(field_name, selection) {
                         map.insert(field_name, value);
                     }
                 }
 
-                ctx.props().on_submit.emit(map);
+                match T::create_from_comparison(map) {
+                    Ok(value) => ctx.props().on_submit.emit(value),
+                    // Stay open so the selection can be changed.
+                    Err(e) => {
+                        log::error!("Unable to create value from comparison: {e}");
+                        self.error = Some(format!("Unable to save: {e}"));
+                    }
+                }
             }
 
-            Msg::SetNewSelected(field, side) => {
-                self.selected.insert(field, side);
+            Msg::SetNewSelected(field, column) => {
+                self.selected.insert(field, FieldSelection::Column(column));
+            }
+
+            Msg::SelectColumn(column) => {
+                for (&field, row) in &ctx.props().compare.fields {
+                    if row.get_column(column).is_some() {
+                        self.selected.insert(field, FieldSelection::Column(column));
+                    }
+                }
+            }
+
+            Msg::ToggleItem(field, column, index) => {
+                let Some(row) = ctx.props().compare.fields.get(field) else {
+                    return false;
+                };
+
+                let selection = self
+                    .selected
+                    .entry(field)
+                    .or_insert(FieldSelection::Column(0));
+
+                selection.toggle_item(row, column, index);
+            }
+
+            Msg::MergeAll(field) => {
+                let Some(row) = ctx.props().compare.fields.get(field) else {
+                    return false;
+                };
+
+                self.selected.insert(
+                    field,
+                    FieldSelection::Items(
+                        (0..row.values.len())
+                            .map(|column| vec![true; row.item_count(column)])
+                            .collect(),
+                    ),
+                );
             }
         }
 
@@ -104,6 +175,15 @@ impl Component for PopupComparison {
                 { self.render_body(ctx) }
 
                 <div class="footer">
+                    {
+                        if let Some(error) = self.error.as_deref() {
+                            html! {
+                                <span class="text-danger" role="alert">{ error }</span>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
                     <button class="button" onclick={ ctx.link().callback(|_| Msg::OnClose) }>{ "Cancel" }</button>
                     <button class="button" onclick={ ctx.link().callback(|_| Msg::OnSubmit) }>{ "Save" }</button>
                 </div>
@@ -112,190 +192,215 @@ impl Component for PopupComparison {
     }
 }
 
-impl PopupComparison {
+impl<T: Comparable + 'static> PopupComparison<T> {
+    fn default_selection(compare: &CompareContainer) -> HashMap<FieldName, FieldSelection> {
+        compare
+            .get_field_names()
+            .into_iter()
+            .map(|v| (v, FieldSelection::Column(0)))
+            .collect()
+    }
+
     fn render_body(&self, ctx: &Context<Self>) -> Html {
         let compare = &ctx.props().compare;
 
         html! {
             <div class="body">
+                <div class="comparison-row comparison-header">
+                    <div class="row-title"></div>
+                    {
+                        for compare.columns.iter().enumerate().map(|(index, column)| html! {
+                            <div class="row-grow column-header" title={ column.source.as_ref().map(|v| v.to_string()) }>
+                                <span>{ column.title.clone() }</span>
+                                <button class="button" onclick={ ctx.link().callback(move |_| Msg::SelectColumn(index)) }>
+                                    { "Select All" }
+                                </button>
+                            </div>
+                        })
+                    }
+                </div>
+
                 {
                     for compare.fields.iter()
                         .map(|(&field, row)| {
-                            let side_selected = self.selected.get(field).copied().unwrap_or(SelectedSide::Left);
-
-                            match row.display {
-                                CompareDisplay::Text => Self::display_value_row(
-                                    field,
-                                    row.title,
-                                    side_selected,
-                                    row.left.as_ref().map(|v| &v.display_value),
-                                    row.right.as_ref().map(|v| &v.display_value),
-                                    ctx
-                                ),
-
-                                CompareDisplay::Image => Self::display_image_row(
-                                    field,
-                                    row.title,
-                                    side_selected,
-                                    row.left.as_ref().map(|v| &v.display_value),
-                                    row.right.as_ref().map(|v| &v.display_value),
-                                    ctx
-                                ),
-                            }
+                            let selection = self.selected.get(field).cloned().unwrap_or(FieldSelection::Column(0));
+
+                            Self::display_row(field, row, &selection, ctx)
                         })
                 }
             </div>
         }
     }
 
-    fn display_value_row(
+    fn display_row(
         updating_field: FieldName,
-        title: &'static str,
-        side_selected: SelectedSide,
-        left_value: Option<&CompareValue>,
-        right_value: Option<&CompareValue>,
+        row: &CompareRow,
+        selection: &FieldSelection,
         ctx: &Context<Self>,
     ) -> Html {
-        let old_selected = side_selected.is_left().then(|| "selected");
-        let new_selected = side_selected.is_right().then(|| "selected");
+        if !row.is_shown(ctx.props().show_equal_rows) {
+            return html! {};
+        }
 
-        match (left_value, right_value) {
-            (Some(left_value), Some(right_value))
-                if left_value != right_value || ctx.props().show_equal_rows =>
-            {
-                html! {
-                    <div class="comparison-row">
-                        <div class="row-title"><span>{ title }</span></div>
-                        <div class={ classes!("row-grow", old_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Left)) }>
-                        {
-                            for left_value.as_vec().iter().map(|v| html! {
-                                <div class="label">{ v }</div>
-                            })
-                        }
-                        </div>
-                        <div class={ classes!("row-grow", new_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Right)) }>
-                        {
-                            for right_value.as_vec().iter().map(|v| html! {
-                                <div class="label">{ v }</div>
-                            })
-                        }
-                        </div>
-                    </div>
-                }
-            }
+        let is_mergeable = row.is_mergeable();
 
-            (None, Some(right_value)) => {
-                html! {
-                    <div class="comparison-row">
-                        <div class="row-title"><span>{ title }</span></div>
-                        <div class={ classes!("row-grow", old_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Left)) }>
-                            <div class="label">{ "(Empty)" }</div>
-                        </div>
-                        <div class={ classes!("row-grow", new_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Right)) }>
-                        {
-                            for right_value.as_vec().iter().map(|v| html! {
-                                <div class="label">{ v }</div>
-                            })
+        html! {
+            <div class="comparison-row">
+                <div class="row-title">
+                    <span>{ row.title }</span>
+                    {
+                        if is_mergeable {
+                            html! {
+                                <button class="button" title="Take every item from every column" onclick={ ctx.link().callback(move |_| Msg::MergeAll(updating_field)) }>
+                                    { "Merge" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
                         }
-                        </div>
-                    </div>
+                    }
+                </div>
+                {
+                    for (0..row.values.len()).map(|column| {
+                        Self::display_column(updating_field, row, column, selection, is_mergeable, ctx)
+                    })
                 }
-            }
-
-            _ => html! {},
+            </div>
         }
     }
 
-    fn display_image_row(
+    fn display_column(
         updating_field: FieldName,
-        title: &'static str,
-        side_selected: SelectedSide,
-        left_value: Option<&CompareValue>,
-        right_value: Option<&CompareValue>,
+        row: &CompareRow,
+        column: usize,
+        selection: &FieldSelection,
+        is_mergeable: bool,
         ctx: &Context<Self>,
     ) -> Html {
-        let old_selected = side_selected.is_left().then(|| "selected");
-        let new_selected = side_selected.is_right().then(|| "selected");
+        let classes = classes!(
+            "row-grow",
+            selection.is_column_selected(column).then_some("selected"),
+            selection.is_column_partial(column).then_some("partial"),
+            (row.agreement(column) != 0).then_some("agree"),
+        );
+
+        let Some(value) = row.get_column(column).map(|v| &v.display_value) else {
+            return html! {
+                <div class={ classes } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, column)) }>
+                    <div class="label">{ "(Empty)" }</div>
+                </div>
+            };
+        };
 
-        match (left_value, right_value) {
-            (Some(left_value), Some(right_value))
-                if left_value != right_value || ctx.props().show_equal_rows =>
+        html! {
+            <div class={ classes } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, column)) }>
             {
-                html! {
-                    <div class="comparison-row">
-                        <div class="row-title"><span>{ title }</span></div>
-                        <div class={ classes!("row-grow", old_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Left)) }>
-                            {
-                                for left_value.as_vec().iter().map(|v| {
-                                    html! {
-                                        <div class="label">
-                                            <div class="poster">
-                                                <img src={ v.to_string() } />
-                                            </div>
-                                        </div>
-                                    }
-                                })
-                            }
-                        </div>
-                        <div class={ classes!("row-grow", new_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Right)) }>
-                        {
-                            for right_value.as_vec().iter().map(|v| {
-                                html! {
-                                    <div class="label">
-                                        <div class="poster">
-                                            <img src={ v.to_string() } />
-                                        </div>
-                                    </div>
-                                }
-                            })
+                for value.as_vec().into_iter().enumerate().map(|(index, v)| {
+                    let display = match row.display {
+                        CompareDisplay::Text => html! { { v } },
+                        CompareDisplay::Image => html! {
+                            <div class="poster">
+                                <img src={ v.to_string() } />
+                            </div>
+                        },
+                    };
+
+                    let agree = (is_mergeable && row.item_agreement(column, v) != 0).then_some("agree");
+
+                    if is_mergeable {
+                        html! {
+                            <label
+                                class={ classes!("label", agree) }
+                                // Clicking the text toggles the input. Don't select the whole column as well.
+                                onclick={ Callback::from(|e: MouseEvent| e.stop_propagation()) }
+                            >
+                                <input
+                                    type="checkbox"
+                                    checked={ selection.is_item_selected(column, index) }
+                                    onclick={ ctx.link().callback(move |e: MouseEvent| {
+                                        // Don't select the whole column.
+                                        e.stop_propagation();
+                                        Msg::ToggleItem(updating_field, column, index)
+                                    }) }
+                                />
+                                { display }
+                            </label>
                         }
-                        </div>
-                    </div>
-                }
-            }
-
-            (None, Some(new_images)) => {
-                html! {
-                    <div class="comparison-row">
-                        <div class="row-title"><span>{ title }</span></div>
-                        <div class={ classes!("row-grow", old_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Left)) }>
-                            <div class="label">{ "(Empty)" }</div>
-                        </div>
-                        <div class={ classes!("row-grow", new_selected) } onclick={ ctx.link().callback(move |_| Msg::SetNewSelected(updating_field, SelectedSide::Right)) }>
-                        {
-                            for new_images.as_vec().iter().map(|v| {
-                                html! {
-                                    <div class="label">
-                                        <div class="poster">
-                                            <img src={ v.to_string() } />
-                                        </div>
-                                    </div>
-                                }
-                            })
+                    } else {
+                        html! {
+                            <div class="label">{ display }</div>
                         }
-                        </div>
-                    </div>
-                }
+                    }
+                })
             }
-
-            _ => html! {},
+            </div>
         }
     }
 }
 
 pub type MapContainer = HashMap<FieldName, MapValue>;
 
+/// Header of a column in the comparison. The first column is the value being edited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareColumn {
+    pub title: String,
+    /// Where the values came from.
+    pub source: Option<Source>,
+}
+
+impl CompareColumn {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            source: None,
+        }
+    }
+}
+
+impl From<Source> for CompareColumn {
+    fn from(value: Source) -> Self {
+        Self {
+            title: value.agent.to_string(),
+            source: Some(value),
+        }
+    }
+}
+
+impl From<Agent> for CompareColumn {
+    fn from(value: Agent) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct CompareContainer {
+    columns: Vec<CompareColumn>,
     fields: HashMap<FieldName, CompareRow>,
 }
 
 impl CompareContainer {
+    /// Compares the current value (left) against a single candidate (right).
     pub fn create(
         displays: Vec<(FieldName, &'static str, CompareDisplay)>,
-        mut left: MapContainer,
-        mut right: MapContainer,
+        left: MapContainer,
+        right: MapContainer,
+    ) -> Self {
+        Self::create_multi(
+            displays,
+            vec![
+                (CompareColumn::new("Current"), left),
+                (CompareColumn::new("New"), right),
+            ],
+        )
+    }
+
+    /// The first column is the current value, the rest are candidates.
+    pub fn create_multi(
+        displays: Vec<(FieldName, &'static str, CompareDisplay)>,
+        columns: Vec<(CompareColumn, MapContainer)>,
     ) -> Self {
+        let (columns, mut maps): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+
         let mut compiled = HashMap::new();
 
         for (field_name, title, display) in displays {
@@ -306,20 +411,26 @@ impl CompareContainer {
 
                     title,
 
-                    left: left.remove(field_name),
-                    right: right.remove(field_name),
+                    values: maps.iter_mut().map(|v| v.remove(field_name)).collect(),
                 },
             );
         }
 
-        Self { fields: compiled }
+        Self {
+            columns,
+            fields: compiled,
+        }
     }
 
-    pub fn get_side_value_map(&self, side: SelectedSide) -> HashMap<FieldName, serde_json::Value> {
+    pub fn columns(&self) -> &[CompareColumn] {
+        &self.columns
+    }
+
+    pub fn get_column_value_map(&self, column: usize) -> HashMap<FieldName, serde_json::Value> {
         let mut map = HashMap::new();
 
         for (&field_name, row) in &self.fields {
-            if let Some(value) = row.get_side_value(side) {
+            if let Some(value) = row.get_column_value(column) {
                 map.insert(field_name, value);
             }
         }
@@ -327,13 +438,44 @@ impl CompareContainer {
         map
     }
 
+    #[inline]
+    pub fn get_side_value_map(&self, side: SelectedSide) -> HashMap<FieldName, serde_json::Value> {
+        self.get_column_value_map(side.column())
+    }
+
     #[inline]
     pub fn get_field_side_value(
         &self,
         field: FieldName,
         side: SelectedSide,
     ) -> Option<serde_json::Value> {
-        self.fields.get(field).and_then(|v| v.get_side_value(side))
+        self.fields
+            .get(field)
+            .and_then(|v| v.get_column_value(side.column()))
+    }
+
+    /// The value to save for the field. `None` if the selected column is empty.
+    pub fn get_field_value(
+        &self,
+        field: FieldName,
+        selection: &FieldSelection,
+    ) -> Option<serde_json::Value> {
+        let row = self.fields.get(field)?;
+
+        match selection {
+            FieldSelection::Column(column) => row.get_column_value(*column),
+            FieldSelection::Items(ticked) => row.merge_items(ticked),
+        }
+    }
+
+    /// Whether individual items of the field can be picked from each column. See [`FieldSelection::Items`].
+    pub fn is_field_mergeable(&self, field: FieldName) -> bool {
+        self.fields.get(field).is_some_and(|v| v.is_mergeable())
+    }
+
+    /// Number of other columns with the same value for the field.
+    pub fn field_agreement(&self, field: FieldName, column: usize) -> usize {
+        self.fields.get(field).map_or(0, |v| v.agreement(column))
     }
 
     pub fn get_field_names(&self) -> Vec<FieldName> {
@@ -356,6 +498,7 @@ pub fn morph_map_value<V: Serialize + Into<CompareValue> + Clone>(
     })
 }
 
+/// Columns of a two way comparison.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SelectedSide {
     Left,
@@ -370,6 +513,74 @@ impl SelectedSide {
     pub fn is_right(self) -> bool {
         !self.is_left()
     }
+
+    pub fn column(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+        }
+    }
+}
+
+/// What to save for a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSelection {
+    /// The whole value of one column.
+    Column(usize),
+    /// Ticked items of an array field per column, in order. Duplicates are only kept once.
+    Items(Vec<Vec<bool>>),
+}
+
+impl From<SelectedSide> for FieldSelection {
+    fn from(value: SelectedSide) -> Self {
+        Self::Column(value.column())
+    }
+}
+
+impl FieldSelection {
+    pub fn is_column_selected(&self, column: usize) -> bool {
+        matches!(self, Self::Column(v) if *v == column)
+    }
+
+    /// Some, but not necessarily all, items of the column are ticked.
+    pub fn is_column_partial(&self, column: usize) -> bool {
+        self.items(column).is_some_and(|v| v.contains(&true))
+    }
+
+    pub fn is_item_selected(&self, column: usize, index: usize) -> bool {
+        match self {
+            Self::Column(v) => *v == column,
+            Self::Items(_) => self
+                .items(column)
+                .and_then(|v| v.get(index))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn items(&self, column: usize) -> Option<&Vec<bool>> {
+        match self {
+            Self::Items(ticked) => ticked.get(column),
+            Self::Column(_) => None,
+        }
+    }
+
+    /// Switches to picking items, starting with every item of the previously selected column.
+    fn toggle_item(&mut self, row: &CompareRow, column: usize, index: usize) {
+        if let Self::Column(selected) = *self {
+            *self = Self::Items(
+                (0..row.values.len())
+                    .map(|v| vec![v == selected; row.item_count(v)])
+                    .collect(),
+            );
+        }
+
+        if let Self::Items(ticked) = self {
+            if let Some(value) = ticked.get_mut(column).and_then(|v| v.get_mut(index)) {
+                *value = !*value;
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -384,22 +595,111 @@ pub(crate) struct CompareRow {
 
     pub title: &'static str,
 
-    pub left: Option<MapValue>,
-    pub right: Option<MapValue>,
+    /// One for each column.
+    pub values: Vec<Option<MapValue>>,
 }
 
 impl CompareRow {
     #[inline]
-    pub fn get_side_value(&self, side: SelectedSide) -> Option<serde_json::Value> {
-        match side {
-            SelectedSide::Left => self.left.as_ref().map(|v| v.original_value.clone()),
-            SelectedSide::Right => self.right.as_ref().map(|v| v.original_value.clone()),
+    pub fn get_column(&self, column: usize) -> Option<&MapValue> {
+        self.values.get(column).and_then(|v| v.as_ref())
+    }
+
+    #[inline]
+    pub fn get_column_value(&self, column: usize) -> Option<serde_json::Value> {
+        self.get_column(column).map(|v| v.original_value.clone())
+    }
+
+    pub fn item_count(&self, column: usize) -> usize {
+        self.get_column(column)
+            .map_or(0, |v| v.display_value.as_vec().len())
+    }
+
+    /// Hidden unless a candidate has a value which differs from the current one.
+    pub fn is_shown(&self, show_equal_rows: bool) -> bool {
+        let current = self.get_column(0).map(|v| &v.display_value);
+
+        self.values
+            .iter()
+            .skip(1)
+            .flatten()
+            .any(|v| show_equal_rows || current != Some(&v.display_value))
+    }
+
+    /// Number of other columns with the same value.
+    pub fn agreement(&self, column: usize) -> usize {
+        let Some(value) = self.get_column(column) else {
+            return 0;
+        };
+
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(index, other)| {
+                *index != column
+                    && other.as_ref().map(|v| &v.display_value) == Some(&value.display_value)
+            })
+            .count()
+    }
+
+    /// Number of other columns which also contain the item.
+    pub fn item_agreement(&self, column: usize, item: &str) -> usize {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(index, other)| {
+                *index != column
+                    && other
+                        .as_ref()
+                        .is_some_and(|v| v.display_value.as_vec().contains(&item))
+            })
+            .count()
+    }
+
+    /// Every column with a value is an array with a displayed item for each saved item.
+    pub fn is_mergeable(&self) -> bool {
+        let is_array = |value: &MapValue| match (&value.display_value, &value.original_value) {
+            (CompareValue::Array(display), serde_json::Value::Array(original)) => {
+                display.len() == original.len()
+            }
+            _ => false,
+        };
+
+        let mut columns = self.values.iter().flatten().peekable();
+
+        columns.peek().is_some() && columns.all(is_array)
+    }
+
+    pub fn merge_items(&self, ticked: &[Vec<bool>]) -> Option<serde_json::Value> {
+        if !self.is_mergeable() {
+            return None;
         }
+
+        let mut merged = Vec::new();
+
+        for (value, ticked) in self.values.iter().zip(ticked) {
+            let Some(serde_json::Value::Array(items)) = value.as_ref().map(|v| &v.original_value)
+            else {
+                continue;
+            };
+
+            for (item, _) in items.iter().zip(ticked).filter(|(_, ticked)| **ticked) {
+                if !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+        }
+
+        Some(serde_json::Value::Array(merged))
     }
 }
 
 pub trait Comparable {
-    fn create_comparison_with(&self, other: &Self) -> serde_json::Result<CompareContainer>;
+    /// Compared fields with their title and how to display them.
+    fn compare_displays() -> Vec<(FieldName, &'static str, CompareDisplay)>
+    where
+        Self: Sized;
+
     fn create_from_comparison(
         map: HashMap<FieldName, serde_json::Value>,
     ) -> serde_json::Result<Self>
@@ -407,6 +707,38 @@ pub trait Comparable {
         Self: Sized;
 
     fn create_map(&self) -> serde_json::Result<MapContainer>;
+
+    fn create_comparison_with(&self, other: &Self) -> serde_json::Result<CompareContainer>
+    where
+        Self: Sized,
+    {
+        Ok(CompareContainer::create(
+            Self::compare_displays(),
+            self.create_map()?,
+            other.create_map()?,
+        ))
+    }
+
+    /// Compares us (the first column) against every candidate.
+    fn create_comparison_with_many<'a>(
+        &self,
+        current: CompareColumn,
+        candidates: impl IntoIterator<Item = (CompareColumn, &'a Self)>,
+    ) -> serde_json::Result<CompareContainer>
+    where
+        Self: Sized + 'a,
+    {
+        let mut columns = vec![(current, self.create_map()?)];
+
+        for (column, candidate) in candidates {
+            columns.push((column, candidate.create_map()?));
+        }
+
+        Ok(CompareContainer::create_multi(
+            Self::compare_displays(),
+            columns,
+        ))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -0,0 +1,467 @@
+//! Ready-made confirmation, alert and prompt dialogs built on [`Popup`].
+//!
+//! For asking from inside of a callback wrap the app in a [`DialogProvider`] and use [`use_confirm`].
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use futures::channel::oneshot;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_hooks::use_list;
+
+use super::{Popup, PopupType};
+
+static NEXT_DIALOG_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Unique id for the dialog's title, used for `aria-labelledby`.
+#[hook]
+fn use_title_id() -> AttrValue {
+    let id = use_state(|| NEXT_DIALOG_ID.fetch_add(1, Ordering::Relaxed));
+
+    AttrValue::from(format!("dialog-title-{}", *id))
+}
+
+fn confirm_classes(destructive: bool) -> Classes {
+    classes!(
+        "btn",
+        if destructive {
+            "btn-danger"
+        } else {
+            "btn-primary"
+        }
+    )
+}
+
+// ==========================
+// Confirm
+// ==========================
+
+#[derive(Properties, PartialEq)]
+pub struct ConfirmDialogProps {
+    pub title: AttrValue,
+
+    /// Message displayed in the body.
+    #[prop_or_default]
+    pub children: Children,
+
+    #[prop_or(AttrValue::Static("Confirm"))]
+    pub confirm_text: AttrValue,
+    #[prop_or(AttrValue::Static("Cancel"))]
+    pub cancel_text: AttrValue,
+
+    /// The action can't be undone. Styles the confirm button as dangerous and focuses "Cancel" instead.
+    #[prop_or_default]
+    pub destructive: bool,
+
+    pub on_confirm: Callback<()>,
+    /// Also called on Escape and when clicking outside of the dialog.
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(ConfirmDialog)]
+pub fn _confirm_dialog(props: &ConfirmDialogProps) -> Html {
+    let title_id = use_title_id();
+
+    let on_confirm = props.on_confirm.reform(|_: MouseEvent| ());
+    let on_cancel = props.on_cancel.reform(|_: MouseEvent| ());
+
+    html! {
+        <Popup
+            classes="dialog-confirm"
+            type_of={ PopupType::FullOverlay }
+            on_close={ props.on_cancel.clone() }
+            aria_labelledby={ title_id.clone() }
+        >
+            <div class="modal-header">
+                <h5 class="modal-title" id={ title_id }>{ props.title.clone() }</h5>
+            </div>
+
+            <div class="modal-body">
+                { for props.children.iter() }
+            </div>
+
+            <div class="modal-footer">
+                <button
+                    type="button"
+                    class="btn btn-secondary"
+                    autofocus={ props.destructive }
+                    onclick={ on_cancel }
+                >
+                    { props.cancel_text.clone() }
+                </button>
+                <button
+                    type="button"
+                    class={ confirm_classes(props.destructive) }
+                    autofocus={ !props.destructive }
+                    onclick={ on_confirm }
+                >
+                    { props.confirm_text.clone() }
+                </button>
+            </div>
+        </Popup>
+    }
+}
+
+// ==========================
+// Alert
+// ==========================
+
+#[derive(Properties, PartialEq)]
+pub struct AlertDialogProps {
+    pub title: AttrValue,
+
+    /// Message displayed in the body.
+    #[prop_or_default]
+    pub children: Children,
+
+    #[prop_or(AttrValue::Static("OK"))]
+    pub close_text: AttrValue,
+
+    pub on_close: Callback<()>,
+}
+
+#[function_component(AlertDialog)]
+pub fn _alert_dialog(props: &AlertDialogProps) -> Html {
+    let title_id = use_title_id();
+
+    html! {
+        <Popup
+            classes="dialog-alert"
+            type_of={ PopupType::FullOverlay }
+            on_close={ props.on_close.clone() }
+            aria_labelledby={ title_id.clone() }
+        >
+            <div class="modal-header">
+                <h5 class="modal-title" id={ title_id }>{ props.title.clone() }</h5>
+            </div>
+
+            <div class="modal-body" role="alert">
+                { for props.children.iter() }
+            </div>
+
+            <div class="modal-footer">
+                <button
+                    type="button"
+                    class="btn btn-primary"
+                    autofocus=true
+                    onclick={ props.on_close.reform(|_: MouseEvent| ()) }
+                >
+                    { props.close_text.clone() }
+                </button>
+            </div>
+        </Popup>
+    }
+}
+
+// ==========================
+// Prompt
+// ==========================
+
+#[derive(Properties, PartialEq)]
+pub struct PromptDialogProps {
+    pub title: AttrValue,
+
+    /// Label for the input.
+    #[prop_or_default]
+    pub label: Option<AttrValue>,
+    #[prop_or_default]
+    pub placeholder: Option<AttrValue>,
+    #[prop_or_default]
+    pub default_value: AttrValue,
+
+    /// Don't allow submitting an empty (or whitespace only) value.
+    #[prop_or_default]
+    pub required: bool,
+
+    #[prop_or(AttrValue::Static("Submit"))]
+    pub submit_text: AttrValue,
+    #[prop_or(AttrValue::Static("Cancel"))]
+    pub cancel_text: AttrValue,
+
+    pub on_submit: Callback<String>,
+    /// Also called on Escape and when clicking outside of the dialog.
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(PromptDialog)]
+pub fn _prompt_dialog(props: &PromptDialogProps) -> Html {
+    let title_id = use_title_id();
+    let input_id = use_memo(title_id.clone(), |id| format!("{id}-input"));
+
+    let value = {
+        let default_value = props.default_value.to_string();
+        use_state_eq(move || default_value)
+    };
+
+    let is_empty = props.required && value.trim().is_empty();
+
+    let on_input = {
+        let value = value.clone();
+
+        Callback::from(move |e: InputEvent| {
+            value.set(
+                e.target()
+                    .unwrap_throw()
+                    .unchecked_into::<HtmlInputElement>()
+                    .value(),
+            );
+        })
+    };
+
+    // Enter inside of the input submits the form.
+    let on_submit = {
+        let value = value.clone();
+        let on_submit = props.on_submit.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+
+            if !is_empty {
+                on_submit.emit((*value).clone());
+            }
+        })
+    };
+
+    html! {
+        <Popup
+            classes="dialog-prompt"
+            type_of={ PopupType::FullOverlay }
+            on_close={ props.on_cancel.clone() }
+            aria_labelledby={ title_id.clone() }
+        >
+            <form onsubmit={ on_submit }>
+                <div class="modal-header">
+                    <h5 class="modal-title" id={ title_id }>{ props.title.clone() }</h5>
+                </div>
+
+                <div class="modal-body">
+                    {
+                        if let Some(label) = props.label.clone() {
+                            html! {
+                                <label class="form-label" for={ (*input_id).clone() }>{ label }</label>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+
+                    <input
+                        id={ (*input_id).clone() }
+                        type="text"
+                        class="form-control"
+                        autofocus=true
+                        placeholder={ props.placeholder.clone() }
+                        value={ (*value).clone() }
+                        oninput={ on_input }
+                    />
+                </div>
+
+                <div class="modal-footer">
+                    <button
+                        type="button"
+                        class="btn btn-secondary"
+                        onclick={ props.on_cancel.reform(|_: MouseEvent| ()) }
+                    >
+                        { props.cancel_text.clone() }
+                    </button>
+                    <button type="submit" class="btn btn-primary" disabled={ is_empty }>
+                        { props.submit_text.clone() }
+                    </button>
+                </div>
+            </form>
+        </Popup>
+    }
+}
+
+// ==========================
+// Imperative
+// ==========================
+
+/// What [`UseConfirmHandle::ask_with`] displays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmOptions {
+    pub title: AttrValue,
+    pub message: AttrValue,
+    pub confirm_text: AttrValue,
+    pub cancel_text: AttrValue,
+    pub destructive: bool,
+}
+
+impl ConfirmOptions {
+    pub fn new(message: impl Into<AttrValue>) -> Self {
+        Self {
+            title: AttrValue::Static("Are you sure?"),
+            message: message.into(),
+            confirm_text: AttrValue::Static("Confirm"),
+            cancel_text: AttrValue::Static("Cancel"),
+            destructive: false,
+        }
+    }
+
+    pub fn title(mut self, value: impl Into<AttrValue>) -> Self {
+        self.title = value.into();
+        self
+    }
+
+    pub fn confirm_text(mut self, value: impl Into<AttrValue>) -> Self {
+        self.confirm_text = value.into();
+        self
+    }
+
+    pub fn cancel_text(mut self, value: impl Into<AttrValue>) -> Self {
+        self.cancel_text = value.into();
+        self
+    }
+
+    pub fn destructive(mut self) -> Self {
+        self.destructive = true;
+        self
+    }
+}
+
+/// A pending [`UseConfirmHandle::ask`].
+#[derive(Clone)]
+struct ConfirmRequest {
+    /// Unique id. Used as the dialog's key.
+    id: usize,
+    options: ConfirmOptions,
+    sender: Rc<RefCell<Option<oneshot::Sender<bool>>>>,
+}
+
+impl PartialEq for ConfirmRequest {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.sender, &other.sender)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct DialogContext {
+    request: Callback<ConfirmRequest>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DialogProviderProps {
+    pub children: Children,
+}
+
+/// Displays the dialogs requested through [`use_confirm`], one at a time in the order they were asked.
+#[function_component(DialogProvider)]
+pub fn _dialog_provider(props: &DialogProviderProps) -> Html {
+    let pending = use_list(Vec::<ConfirmRequest>::new());
+
+    let context = {
+        let pending = pending.clone();
+
+        use_memo((), move |_| DialogContext {
+            request: Callback::from(move |request| pending.push(request)),
+        })
+    };
+
+    let current = pending.current().first().cloned();
+
+    let dialog = current.map(|request| {
+        let respond = {
+            let pending = pending.clone();
+            let request = request.clone();
+
+            move |value: bool| {
+                if let Some(sender) = request.sender.take() {
+                    let _ = sender.send(value);
+                }
+
+                pending.retain(|v| v != &request);
+            }
+        };
+
+        let on_confirm = {
+            let respond = respond.clone();
+            Callback::from(move |_| respond(true))
+        };
+
+        let on_cancel = Callback::from(move |_| respond(false));
+
+        // A new key per request so the dialog is recreated, focusing itself and remembering the new opener.
+        let key = request.id;
+        let options = request.options;
+
+        html! {
+            <ConfirmDialog
+                {key}
+                title={ options.title }
+                confirm_text={ options.confirm_text }
+                cancel_text={ options.cancel_text }
+                destructive={ options.destructive }
+                {on_confirm}
+                {on_cancel}
+            >
+                { options.message }
+            </ConfirmDialog>
+        }
+    });
+
+    html! {
+        <ContextProvider<DialogContext> context={ (*context).clone() }>
+            { for props.children.iter() }
+            { dialog }
+        </ContextProvider<DialogContext>>
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct UseConfirmHandle {
+    context: Option<DialogContext>,
+}
+
+impl UseConfirmHandle {
+    /// Displays a [`ConfirmDialog`] and resolves to `true` if the user confirmed.
+    pub async fn ask(&self, message: impl Into<AttrValue>) -> bool {
+        self.ask_with(ConfirmOptions::new(message)).await
+    }
+
+    /// Like [`Self::ask`] with a customized dialog.
+    ///
+    /// Resolves to `false` if there's no [`DialogProvider`] or it was removed before answering.
+    pub async fn ask_with(&self, options: ConfirmOptions) -> bool {
+        let Some(context) = self.context.as_ref() else {
+            log::error!("use_confirm called outside of a DialogProvider");
+            return false;
+        };
+
+        let (sender, receiver) = oneshot::channel();
+
+        context.request.emit(ConfirmRequest {
+            id: NEXT_DIALOG_ID.fetch_add(1, Ordering::Relaxed),
+            options,
+            sender: Rc::new(RefCell::new(Some(sender))),
+        });
+
+        receiver.await.unwrap_or_default()
+    }
+}
+
+/// Ask the user to confirm from inside of a callback.
+///
+/// ```ignore
+/// let confirm = use_confirm();
+///
+/// let on_delete = Callback::from(move |_| {
+///     let confirm = confirm.clone();
+///
+///     spawn_local(async move {
+///         if confirm.ask_with(ConfirmOptions::new("Delete?").destructive()).await {
+///             // ...
+///         }
+///     });
+/// });
+/// ```
+#[hook]
+pub fn use_confirm() -> UseConfirmHandle {
+    UseConfirmHandle {
+        context: use_context::<DialogContext>(),
+    }
+}
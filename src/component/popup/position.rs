@@ -0,0 +1,243 @@
+//! Keeps popups inside of the viewport.
+//!
+//! The `place_*` functions only do the math so they can be used without a browser.
+
+use gloo_utils::{document, window};
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::{DomRect, Element, HtmlElement, ResizeObserver};
+
+use super::button::ButtonPopupPosition;
+
+/// Minimum distance kept between a popup and the edge of the viewport.
+pub static VIEWPORT_MARGIN: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl From<DomRect> for Rect {
+    fn from(value: DomRect) -> Self {
+        Self {
+            left: value.left(),
+            top: value.top(),
+            width: value.width(),
+            height: value.height(),
+        }
+    }
+}
+
+/// Where a popup ended up after [`place_around`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Differs from the preferred position if there wasn't enough room.
+    pub position: ButtonPopupPosition,
+    pub left: f64,
+    pub top: f64,
+}
+
+/// Places a popup at the point, like a context menu.
+///
+/// Opens to the right and below the point, flips to the other side if it doesn't fit, then shifts to stay in the viewport.
+pub fn place_at_point(x: f64, y: f64, popup: Size, viewport: Size) -> (f64, f64) {
+    let flip = |point: f64, size: f64, max: f64| {
+        if point + size > max - VIEWPORT_MARGIN && point - size >= VIEWPORT_MARGIN {
+            point - size
+        } else {
+            point
+        }
+    };
+
+    (
+        clamp_to_viewport(
+            flip(x, popup.width, viewport.width),
+            popup.width,
+            viewport.width,
+        ),
+        clamp_to_viewport(
+            flip(y, popup.height, viewport.height),
+            popup.height,
+            viewport.height,
+        ),
+    )
+}
+
+/// Places a popup next to the anchor on the preferred side.
+///
+/// Tries the opposite side next, then the other two. If none of them fit it uses the side with the most room.
+pub fn place_around(
+    anchor: Rect,
+    popup: Size,
+    viewport: Size,
+    preferred: ButtonPopupPosition,
+) -> Placement {
+    let space = |position: ButtonPopupPosition| match position {
+        ButtonPopupPosition::Top => anchor.top - VIEWPORT_MARGIN - popup.height,
+        ButtonPopupPosition::Bottom => {
+            viewport.height - anchor.bottom() - VIEWPORT_MARGIN - popup.height
+        }
+        ButtonPopupPosition::Left => anchor.left - VIEWPORT_MARGIN - popup.width,
+        ButtonPopupPosition::Right => {
+            viewport.width - anchor.right() - VIEWPORT_MARGIN - popup.width
+        }
+    };
+
+    let order = [
+        preferred,
+        preferred.opposite(),
+        preferred.rotate(),
+        preferred.rotate().opposite(),
+    ];
+
+    let position = order
+        .into_iter()
+        .find(|v| space(*v) >= 0.0)
+        .unwrap_or_else(|| {
+            order.into_iter().fold(
+                preferred,
+                |best, v| if space(v) > space(best) { v } else { best },
+            )
+        });
+
+    let (left, top) = match position {
+        ButtonPopupPosition::Top => (anchor.left, anchor.top - popup.height),
+        ButtonPopupPosition::Bottom => (anchor.left, anchor.bottom()),
+        ButtonPopupPosition::Left => (anchor.left - popup.width, anchor.top),
+        ButtonPopupPosition::Right => (anchor.right(), anchor.top),
+    };
+
+    Placement {
+        position,
+        left: clamp_to_viewport(left, popup.width, viewport.width),
+        top: clamp_to_viewport(top, popup.height, viewport.height),
+    }
+}
+
+fn clamp_to_viewport(start: f64, size: f64, max: f64) -> f64 {
+    start.min(max - VIEWPORT_MARGIN - size).max(VIEWPORT_MARGIN)
+}
+
+/// Size of the viewport excluding scrollbars.
+pub fn viewport_size() -> Size {
+    let element = document().document_element();
+
+    Size {
+        width: element.as_ref().map_or(0, |v| v.client_width()) as f64,
+        height: element.as_ref().map_or(0, |v| v.client_height()) as f64,
+    }
+}
+
+/// Moves the element to the point using fixed positioning. See [`place_at_point`].
+pub fn position_at_point(element: &HtmlElement, x: f64, y: f64) {
+    let size = Rect::from(element.get_bounding_client_rect()).size();
+
+    let (left, top) = place_at_point(x, y, size, viewport_size());
+
+    set_fixed_position(element, left, top);
+}
+
+/// Moves the element next to the anchor using fixed positioning. See [`place_around`].
+pub fn position_around(
+    anchor: &Element,
+    element: &HtmlElement,
+    preferred: ButtonPopupPosition,
+) -> ButtonPopupPosition {
+    let placement = place_around(
+        anchor.get_bounding_client_rect().into(),
+        Rect::from(element.get_bounding_client_rect()).size(),
+        viewport_size(),
+        preferred,
+    );
+
+    set_fixed_position(element, placement.left, placement.top);
+
+    placement.position
+}
+
+fn set_fixed_position(element: &HtmlElement, left: f64, top: f64) {
+    let style = element.style();
+
+    let _ = style.set_property("position", "fixed");
+    let _ = style.set_property("left", &format!("{left}px"));
+    let _ = style.set_property("top", &format!("{top}px"));
+    let _ = style.set_property("right", "auto");
+    let _ = style.set_property("bottom", "auto");
+    let _ = style.set_property("margin", "0");
+    let _ = style.set_property("transform", "none");
+}
+
+/// Calls the function whenever the observed elements resize, the window resizes or anything scrolls.
+///
+/// Stops once dropped.
+pub struct RepositionObserver {
+    observer: Option<ResizeObserver>,
+    closure: Closure<dyn FnMut()>,
+}
+
+impl RepositionObserver {
+    pub fn new(elements: &[&Element], on_change: impl FnMut() + 'static) -> Self {
+        let closure = Closure::wrap(Box::new(on_change) as Box<dyn FnMut()>);
+
+        let observer = ResizeObserver::new(closure.as_ref().unchecked_ref()).ok();
+
+        if let Some(observer) = observer.as_ref() {
+            for element in elements {
+                observer.observe(element);
+            }
+        }
+
+        let window = window();
+        let _ = window.add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref());
+        // Capture scrolling of any container, not just the document.
+        let _ = window.add_event_listener_with_callback_and_bool(
+            "scroll",
+            closure.as_ref().unchecked_ref(),
+            true,
+        );
+
+        Self { observer, closure }
+    }
+}
+
+impl Drop for RepositionObserver {
+    fn drop(&mut self) {
+        if let Some(observer) = self.observer.take() {
+            observer.disconnect();
+        }
+
+        let window = window();
+        let _ = window
+            .remove_event_listener_with_callback("resize", self.closure.as_ref().unchecked_ref());
+        let _ = window.remove_event_listener_with_callback_and_bool(
+            "scroll",
+            self.closure.as_ref().unchecked_ref(),
+            true,
+        );
+    }
+}
@@ -1,59 +1,208 @@
-use std::collections::HashMap;
-
-use gloo_utils::document;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use futures::future::{abortable, AbortHandle};
+use gloo_timers::callback::Timeout;
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
-use web_sys::HtmlInputElement;
+use web_sys::{Element, HtmlInputElement};
 use yew::prelude::*;
 
-use crate::util::{truncate_on_indices, upper_case_first_char, LoadingItem};
+use crate::util::{strip_html, truncate_with_ellipsis, upper_case_first_char};
 
 use super::{Popup, PopupType};
 
-type SearchResponse<Id> = std::result::Result<HashMap<String, Vec<SearchItem<Id>>>, String>;
+static NEXT_SEARCH_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A single result displayed by [`PopupSearch`].
+pub trait SearchResult: Clone + PartialEq + 'static {
+    type Id: Clone + PartialEq + 'static;
+
+    /// Passed to `on_select` once clicked.
+    fn id(&self) -> Self::Id;
+
+    /// Contents of the clickable result. `tab` is the agent which returned it.
+    fn render(&self, tab: &str) -> Html;
+}
+
+/// Where to continue from when loading more results for a tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchPage {
+    Offset(usize),
+    Cursor(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchRequest {
+    pub query: String,
+    /// Only set when loading more. Results are only needed for this tab.
+    pub tab: Option<String>,
+    pub page: Option<SearchPage>,
+}
+
+/// Results of a single agent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchTab<Item> {
+    pub items: Vec<Item>,
+    /// `None` if there aren't any more results.
+    pub next: Option<SearchPage>,
+}
+
+impl<Item> SearchTab<Item> {
+    pub fn new(items: Vec<Item>) -> Self {
+        Self { items, next: None }
+    }
+
+    pub fn with_next(mut self, value: SearchPage) -> Self {
+        self.next = Some(value);
+        self
+    }
+}
+
+impl<Item> From<Vec<Item>> for SearchTab<Item> {
+    fn from(value: Vec<Item>) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Results grouped by the agent (tab) which returned them.
+pub type SearchResponse<Item> = std::result::Result<HashMap<String, SearchTab<Item>>, String>;
+
+/// Tabs sorted by name.
+type SearchTabs<Item> = Vec<(String, SearchTab<Item>)>;
+
+pub type SearchFuture<Item> = Pin<Box<dyn Future<Output = SearchResponse<Item>>>>;
+
+/// Async function called with the current input.
+pub struct SearchFn<Item>(Rc<dyn Fn(SearchRequest) -> SearchFuture<Item>>);
+
+impl<Item> SearchFn<Item> {
+    pub fn new<F, Fut>(func: F) -> Self
+    where
+        F: Fn(SearchRequest) -> Fut + 'static,
+        Fut: Future<Output = SearchResponse<Item>> + 'static,
+    {
+        Self(Rc::new(move |value| Box::pin(func(value))))
+    }
+
+    pub fn call(&self, value: SearchRequest) -> SearchFuture<Item> {
+        (self.0)(value)
+    }
+}
+
+impl<Item> Clone for SearchFn<Item> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Item> PartialEq for SearchFn<Item> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
 
 #[derive(Properties, PartialEq)]
-pub struct Property<Id: PartialEq> {
+pub struct Property<Item: SearchResult> {
     #[prop_or_default]
     pub classes: Classes,
 
+    #[prop_or(AttrValue::Static("Book Search"))]
+    pub title: AttrValue,
+    #[prop_or(AttrValue::Static("Search For Title"))]
+    pub placeholder: AttrValue,
+
     pub on_close: Callback<()>,
-    pub on_select: Callback<Id>,
+    pub on_select: Callback<Item::Id>,
 
-    // TODO: Use fn(String) -> impl Future<Output = SearchResponse>
-    // Currently using a hackish way. Would be better to make it simply return the requests' future.
-    pub call_search: Callback<CallSearch<Id>>,
+    pub search: SearchFn<Item>,
 
+    /// Searched for immediately if not empty.
     pub input_value: String,
+
+    /// Milliseconds to wait after the last keystroke before searching.
+    #[prop_or(300)]
+    pub debounce: u32,
 }
 
-pub enum Msg<Id> {
-    BookSearchResponse(String, SearchResponse<Id>),
+pub enum Msg<Item: SearchResult> {
+    OnInput(String),
+    OnKeyDown(KeyboardEvent),
+    /// Enter or the Search button. Selects the highlighted item or searches right away.
+    Submit,
 
     SearchFor(String),
+    SearchResponse(usize, SearchResponse<Item>),
+
+    LoadMore(String),
+    LoadMoreResponse(usize, String, SearchResponse<Item>),
 
     OnChangeTab(String),
-    OnSelectItem(Id),
+    OnSelectItem(Item::Id),
 }
 
-pub struct PopupSearch<Id> {
-    cached_posters: Option<LoadingItem<SearchResponse<Id>>>,
+pub struct PopupSearch<Item: SearchResult> {
+    list_ref: NodeRef,
+    /// Prefix for the element ids so multiple instances don't collide.
+    element_id: String,
+
     input_value: String,
+    /// Value of the displayed results. Used when loading more.
+    searched_value: String,
+
+    /// Kept while a new search is loading.
+    results: Option<std::result::Result<SearchTabs<Item>, String>>,
+    is_loading: bool,
+    /// Tabs currently loading more results.
+    loading_more: HashSet<String>,
 
     selected_tab: String,
+    /// Item in the selected tab focused with the arrow keys.
+    highlighted: Option<usize>,
+
+    debounce: Option<Timeout>,
+    /// Incremented for each search. Responses for older searches are ignored.
+    request_id: usize,
+    abort_handles: Vec<AbortHandle>,
 
     waiting_item_resp: bool,
 }
 
-impl<Id: Clone + PartialEq + 'static> Component for PopupSearch<Id> {
-    type Message = Msg<Id>;
-    type Properties = Property<Id>;
+impl<Item: SearchResult> Component for PopupSearch<Item> {
+    type Message = Msg<Item>;
+    type Properties = Property<Item>;
 
     fn create(ctx: &Context<Self>) -> Self {
+        let input_value = ctx.props().input_value.clone();
+
+        if !input_value.trim().is_empty() {
+            ctx.link().send_message(Msg::SearchFor(input_value.clone()));
+        }
+
         Self {
-            cached_posters: None,
-            input_value: ctx.props().input_value.clone(),
+            list_ref: NodeRef::default(),
+            element_id: format!(
+                "popup-search-{}",
+                NEXT_SEARCH_ID.fetch_add(1, Ordering::Relaxed)
+            ),
+
+            input_value,
+            searched_value: String::new(),
+
+            results: None,
+            is_loading: false,
+            loading_more: HashSet::new(),
 
             selected_tab: String::new(),
+            highlighted: None,
+
+            debounce: None,
+            request_id: 0,
+            abort_handles: Vec::new(),
 
             waiting_item_resp: false,
         }
@@ -61,24 +210,148 @@ impl<Id: Clone + PartialEq + 'static> Component for PopupSearch<Id> {
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
+            Msg::OnInput(value) => {
+                self.input_value = value.clone();
+
+                if value.trim().is_empty() {
+                    self.cancel_search();
+                    self.results = None;
+                    self.highlighted = None;
+                } else {
+                    // The previous query is stale now. Don't wait for the debounce to drop its results.
+                    self.cancel_search();
+
+                    let link = ctx.link().clone();
+
+                    self.debounce = Some(Timeout::new(ctx.props().debounce, move || {
+                        link.send_message(Msg::SearchFor(value))
+                    }));
+                }
+            }
+
             Msg::SearchFor(search) => {
-                self.cached_posters = Some(LoadingItem::Loading);
-
-                ctx.props().call_search.emit(CallSearch {
-                    search: search.clone(),
-                    response: ctx
-                        .link()
-                        .callback(move |v| Msg::BookSearchResponse(search.clone(), v)),
-                });
+                self.cancel_search();
+
+                self.is_loading = true;
+                self.searched_value = search.clone();
+
+                let request_id = self.request_id;
+
+                self.spawn_search(
+                    ctx,
+                    SearchRequest {
+                        query: search,
+                        tab: None,
+                        page: None,
+                    },
+                    move |resp| Msg::SearchResponse(request_id, resp),
+                );
+            }
+
+            Msg::SearchResponse(request_id, resp) => {
+                if request_id != self.request_id {
+                    return false;
+                }
+
+                self.is_loading = false;
+                self.highlighted = None;
+
+                self.results = Some(resp.map(|map| {
+                    let mut tabs = map.into_iter().collect::<Vec<_>>();
+                    tabs.sort_by(|a, b| a.0.cmp(&b.0));
+                    tabs
+                }));
+
+                // Stay on the same tab if it's still there.
+                if let Some(Ok(tabs)) = self.results.as_ref() {
+                    if !tabs.iter().any(|(name, _)| name == &self.selected_tab) {
+                        self.selected_tab = tabs.first().map(|v| v.0.clone()).unwrap_or_default();
+                    }
+                }
+            }
+
+            Msg::LoadMore(tab) => {
+                let Some(page) = self.find_tab(&tab).and_then(|v| v.next.clone()) else {
+                    return false;
+                };
+
+                if !self.loading_more.insert(tab.clone()) {
+                    return false;
+                }
+
+                let request_id = self.request_id;
+
+                self.spawn_search(
+                    ctx,
+                    SearchRequest {
+                        query: self.searched_value.clone(),
+                        tab: Some(tab.clone()),
+                        page: Some(page),
+                    },
+                    move |resp| Msg::LoadMoreResponse(request_id, tab, resp),
+                );
             }
 
-            Msg::BookSearchResponse(search, resp) => {
-                if let Some(name) = resp.as_ref().ok().and_then(|v| v.keys().next().cloned()) {
-                    self.selected_tab = name;
+            Msg::LoadMoreResponse(request_id, tab, resp) => {
+                if request_id != self.request_id {
+                    return false;
                 }
 
-                self.cached_posters = Some(LoadingItem::Loaded(resp));
-                self.input_value = search;
+                self.loading_more.remove(&tab);
+
+                let loaded = match resp {
+                    Ok(mut map) => map.remove(&tab),
+                    Err(e) => {
+                        log::error!("Unable to load more results for {tab}: {e}");
+                        None
+                    }
+                };
+
+                if let (Some(loaded), Some(Ok(tabs))) = (loaded, self.results.as_mut()) {
+                    if let Some((_, current)) = tabs.iter_mut().find(|(name, _)| name == &tab) {
+                        current.items.extend(loaded.items);
+                        current.next = loaded.next;
+                    }
+                }
+            }
+
+            Msg::OnKeyDown(event) => {
+                let count = self.selected_items().len();
+
+                match event.key().as_str() {
+                    "ArrowDown" if count != 0 => {
+                        event.prevent_default();
+
+                        self.highlighted = Some(self.highlighted.map_or(0, |v| (v + 1) % count));
+                    }
+
+                    "ArrowUp" if count != 0 => {
+                        event.prevent_default();
+
+                        self.highlighted = Some(
+                            self.highlighted
+                                .map_or(count - 1, |v| v.checked_sub(1).unwrap_or(count - 1)),
+                        );
+                    }
+
+                    _ => return false,
+                }
+            }
+
+            Msg::Submit => {
+                let item = self
+                    .highlighted
+                    .and_then(|index| self.selected_items().get(index))
+                    .map(|v| v.id());
+
+                if let Some(id) = item {
+                    ctx.link().send_message(Msg::OnSelectItem(id));
+                } else if !self.input_value.trim().is_empty() {
+                    ctx.link()
+                        .send_message(Msg::SearchFor(self.input_value.clone()));
+                }
+
+                return false;
             }
 
             Msg::OnSelectItem(id) => {
@@ -93,6 +366,7 @@ impl<Id: Clone + PartialEq + 'static> Component for PopupSearch<Id> {
 
             Msg::OnChangeTab(name) => {
                 self.selected_tab = name;
+                self.highlighted = None;
             }
         }
 
@@ -102,81 +376,162 @@ impl<Id: Clone + PartialEq + 'static> Component for PopupSearch<Id> {
     fn view(&self, ctx: &Context<Self>) -> Html {
         self.render_main(ctx)
     }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        // Keep the highlighted item visible while using the arrow keys.
+        if let Some(index) = self.highlighted {
+            let item = self
+                .list_ref
+                .cast::<Element>()
+                .and_then(|list| list.children().item(index as u32));
+
+            if let Some(item) = item {
+                item.scroll_into_view_with_bool(false);
+            }
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.cancel_search();
+    }
 }
 
-impl<Id: Clone + PartialEq + 'static> PopupSearch<Id> {
+impl<Item: SearchResult> PopupSearch<Item> {
+    fn spawn_search(
+        &mut self,
+        ctx: &Context<Self>,
+        request: SearchRequest,
+        into_msg: impl FnOnce(SearchResponse<Item>) -> Msg<Item> + 'static,
+    ) {
+        let (future, handle) = abortable(ctx.props().search.call(request));
+
+        self.abort_handles.push(handle);
+
+        let link = ctx.link().clone();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            // Aborted once a newer search starts.
+            if let Ok(resp) = future.await {
+                link.send_message(into_msg(resp));
+            }
+        });
+    }
+
+    /// Stops any pending or in-flight requests. Responses which still arrive are ignored.
+    fn cancel_search(&mut self) {
+        self.debounce = None;
+        self.request_id += 1;
+
+        for handle in self.abort_handles.drain(..) {
+            handle.abort();
+        }
+
+        self.is_loading = false;
+        self.loading_more.clear();
+    }
+
+    fn find_tab(&self, name: &str) -> Option<&SearchTab<Item>> {
+        match self.results.as_ref() {
+            Some(Ok(tabs)) => tabs.iter().find(|v| v.0 == name).map(|v| &v.1),
+            _ => None,
+        }
+    }
+
+    fn selected_items(&self) -> &[Item] {
+        self.find_tab(&self.selected_tab)
+            .map(|v| v.items.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn item_id(&self, index: usize) -> String {
+        format!("{}-item-{index}", self.element_id)
+    }
+
     fn render_main(&self, ctx: &Context<Self>) -> Html {
-        let input_id = "external-book-search-input";
+        let list_id = format!("{}-list", self.element_id);
 
         html! {
             <Popup
                 type_of={ PopupType::FullOverlay }
                 on_close={ ctx.props().on_close.clone() }
-                classes={ classes!("external-book-search-popup") }
+                classes={ classes!("external-book-search-popup", ctx.props().classes.clone()) }
             >
-                <h1>{"Book Search"}</h1>
-
-                <form class="row">
-                    <input id={input_id} name="book_search" placeholder="Search For Title" value={ self.input_value.clone() } />
-                    <button onclick={
-                        ctx.link().callback(move |e: MouseEvent| {
-                            e.prevent_default();
-
-                            let input = document().get_element_by_id(input_id).unwrap_throw().unchecked_into::<HtmlInputElement>();
-
-                            Msg::SearchFor(input.value())
-                        })
-                    }>{ "Search" }</button>
+                <h1>{ ctx.props().title.clone() }</h1>
+
+                <form class="row" onsubmit={ ctx.link().callback(|e: SubmitEvent| {
+                    e.prevent_default();
+                    Msg::Submit
+                }) }>
+                    <input
+                        name="search"
+                        placeholder={ ctx.props().placeholder.clone() }
+                        autocomplete="off"
+                        autofocus=true
+                        role="combobox"
+                        aria-autocomplete="list"
+                        aria-expanded={ (!self.selected_items().is_empty()).to_string() }
+                        aria-controls={ list_id.clone() }
+                        aria-activedescendant={ self.highlighted.map(|v| self.item_id(v)) }
+                        value={ self.input_value.clone() }
+                        oninput={ ctx.link().callback(|e: InputEvent| {
+                            Msg::OnInput(e.target().unwrap_throw().unchecked_into::<HtmlInputElement>().value())
+                        }) }
+                        onkeydown={ ctx.link().callback(Msg::OnKeyDown) }
+                    />
+                    <button type="submit">{ "Search" }</button>
                 </form>
 
                 <hr />
 
-                <div class="external-book-search-container">
+                <div class={ classes!("external-book-search-container", self.is_loading.then_some("loading")) }>
                     {
-                        if let Some(loading) = self.cached_posters.as_ref() {
-                            match loading {
-                                LoadingItem::Loaded(res) => {
-                                    match res {
-                                        Ok(map) => html! {
-                                            <>
-                                                <div class="tab-bar">
-                                                {
-                                                    for map.iter()
-                                                        .map(|(name, values)| {
-                                                            let name2 = name.clone();
-
-                                                            html! {
-                                                                <div class="tab-bar-item" onclick={ ctx.link().callback(move |_| Msg::OnChangeTab(name2.clone())) }>
-                                                                    { upper_case_first_char(name.clone()) } { format!(" ({})", values.len()) }
-                                                                </div>
-                                                            }
-                                                        })
+                        match self.results.as_ref() {
+                            Some(Ok(tabs)) => html! {
+                                <>
+                                    <div class="tab-bar" role="tablist">
+                                    {
+                                        for tabs.iter()
+                                            .map(|(name, tab)| {
+                                                let name2 = name.clone();
+                                                let is_selected = name == &self.selected_tab;
+
+                                                html! {
+                                                    <div
+                                                        class={ classes!("tab-bar-item", is_selected.then_some("active")) }
+                                                        role="tab"
+                                                        aria-selected={ is_selected.to_string() }
+                                                        onclick={ ctx.link().callback(move |_| Msg::OnChangeTab(name2.clone())) }
+                                                    >
+                                                        { upper_case_first_char(name.clone()) }
+                                                        { format!(" ({}{})", tab.items.len(), if tab.next.is_some() { "+" } else { "" }) }
+                                                    </div>
                                                 }
-                                                </div>
-
-                                                <div class="book-search-items">
-                                                {
-                                                    for map.get(&self.selected_tab)
-                                                        .iter()
-                                                        .flat_map(|values| values.iter())
-                                                        .map(|item| Self::render_poster_container(&self.selected_tab, item, ctx))
-                                                }
-                                                </div>
-                                            </>
-                                        },
-
-                                        Err(e) => html! {
-                                            <h2>{ e }</h2>
-                                        },
+                                            })
                                     }
-                                },
-
-                                LoadingItem::Loading => html! {
-                                    <h2>{ "Loading..." }</h2>
-                                }
-                            }
-                        } else {
-                            html! {}
+                                    </div>
+
+                                    <div ref={ self.list_ref.clone() } id={ list_id } class="book-search-items" role="listbox">
+                                    {
+                                        for self.selected_items()
+                                            .iter()
+                                            .enumerate()
+                                            .map(|(index, item)| self.render_item(index, item, ctx))
+                                    }
+                                    </div>
+
+                                    { self.render_load_more(ctx) }
+                                </>
+                            },
+
+                            Some(Err(e)) => html! {
+                                <h2>{ e }</h2>
+                            },
+
+                            None if self.is_loading => html! {
+                                <h2>{ "Loading..." }</h2>
+                            },
+
+                            None => html! {},
                         }
                     }
                 </div>
@@ -184,30 +539,50 @@ impl<Id: Clone + PartialEq + 'static> PopupSearch<Id> {
         }
     }
 
-    fn render_poster_container(site: &str, item: &SearchItem<Id>, ctx: &Context<Self>) -> Html {
-        let id = item.id.clone();
+    fn render_item(&self, index: usize, item: &Item, ctx: &Context<Self>) -> Html {
+        let id = item.id();
+        let is_highlighted = self.highlighted == Some(index);
 
         html! {
             <div
-                class="book-search-item"
+                id={ self.item_id(index) }
+                class={ classes!("book-search-item", is_highlighted.then_some("active")) }
+                role="option"
+                aria-selected={ is_highlighted.to_string() }
                 onclick={ ctx.link().callback(move |_| Msg::OnSelectItem(id.clone())) }
             >
-                <img src={ item.thumbnail_url.clone() } alt="No Image" />
-                <div class="book-info">
-                    <h4 class="book-name">{ item.name.as_deref().unwrap_or("(Missing Name)") }</h4>
-                    <h5>{ site }</h5>
-                    <span class="book-author">{ item.author.clone().unwrap_or_default() }</span>
-                    <p class="book-author">{ item.description.clone()
-                            .map(|mut v| { truncate_on_indices(&mut v, 300); v })
-                            .unwrap_or_default() }
-                    </p>
-                </div>
+                { item.render(&self.selected_tab) }
             </div>
         }
     }
+
+    fn render_load_more(&self, ctx: &Context<Self>) -> Html {
+        let has_more = self
+            .find_tab(&self.selected_tab)
+            .is_some_and(|v| v.next.is_some());
+
+        if !has_more {
+            return html! {};
+        }
+
+        let is_loading = self.loading_more.contains(&self.selected_tab);
+        let tab = self.selected_tab.clone();
+
+        html! {
+            <button
+                type="button"
+                class="btn btn-secondary load-more"
+                disabled={ is_loading }
+                onclick={ ctx.link().callback(move |_| Msg::LoadMore(tab.clone())) }
+            >
+                { if is_loading { "Loading..." } else { "Load More" } }
+            </button>
+        }
+    }
 }
 
-#[derive(Clone)]
+/// Book result. Used for searching external agents.
+#[derive(Clone, PartialEq)]
 pub struct SearchItem<Id> {
     pub id: Id,
 
@@ -218,7 +593,27 @@ pub struct SearchItem<Id> {
     pub author: Option<String>,
 }
 
-pub struct CallSearch<Id> {
-    pub search: String,
-    pub response: Callback<SearchResponse<Id>>,
+impl<Id: Clone + PartialEq + 'static> SearchResult for SearchItem<Id> {
+    type Id = Id;
+
+    fn id(&self) -> Self::Id {
+        self.id.clone()
+    }
+
+    fn render(&self, tab: &str) -> Html {
+        html! {
+            <>
+                <img src={ self.thumbnail_url.clone() } alt="No Image" />
+                <div class="book-info">
+                    <h4 class="book-name">{ self.name.as_deref().unwrap_or("(Missing Name)") }</h4>
+                    <h5>{ tab.to_string() }</h5>
+                    <span class="book-author">{ self.author.clone().unwrap_or_default() }</span>
+                    <p class="book-author">{ self.description.as_deref()
+                            .map(|v| truncate_with_ellipsis(&strip_html(v), 300))
+                            .unwrap_or_default() }
+                    </p>
+                </div>
+            </>
+        }
+    }
 }
@@ -0,0 +1,289 @@
+//! Keeps track of every open [`Popup`](super::Popup) so nested popups close in the right order.
+//!
+//! Wrap the app in a [`PopupStackProvider`]. Popups inside of it register themselves when created and the provider
+//! then routes outside clicks, Escape and Tab only to the topmost one.
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo_utils::{body, document};
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::{KeyboardEvent, MouseEvent};
+use yew::prelude::*;
+
+use super::{handle_keydown, handle_outside_click, PopupType};
+
+/// z-index of the bottom popup. Each popup above it gets [`POPUP_Z_INDEX_STEP`] more.
+pub static POPUP_BASE_Z_INDEX: i32 = 1050;
+pub static POPUP_Z_INDEX_STEP: i32 = 10;
+
+/// Identifies a popup inside of a [`PopupStack`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PopupHandle(usize);
+
+struct StackEntry {
+    handle: PopupHandle,
+    type_of: PopupType,
+    node_ref: NodeRef,
+    on_close: Callback<()>,
+    opened_at: f64,
+}
+
+#[derive(Default)]
+struct StackInner {
+    next_id: usize,
+    entries: Vec<StackEntry>,
+
+    /// Increased whenever a popup is added or removed.
+    version: usize,
+    /// Re-renders the provider so popups depending on their place in the stack are updated.
+    on_change: Option<Callback<()>>,
+}
+
+/// Open popups, ordered from the bottom to the top.
+#[derive(Clone, Default)]
+pub struct PopupStack {
+    inner: Rc<RefCell<StackInner>>,
+    /// Version this copy was taken at. Lets the context notify consumers when the stack changes.
+    version: usize,
+}
+
+impl PartialEq for PopupStack {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner) && self.version == other.version
+    }
+}
+
+impl PopupStack {
+    /// Copy of the stack at its current version.
+    fn snapshot(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            version: self.inner.borrow().version,
+        }
+    }
+
+    fn changed(&self) {
+        let on_change = {
+            let mut inner = self.inner.borrow_mut();
+            inner.version += 1;
+            inner.on_change.clone()
+        };
+
+        // Emitted after the borrow ends since the callback may touch the stack.
+        if let Some(on_change) = on_change {
+            on_change.emit(());
+        }
+    }
+
+    /// Places the popup on top of the stack. Called by [`Popup`](super::Popup) when it's created.
+    ///
+    /// `opened_at` uses the same clock as `Event::time_stamp`. Clicks from before it are ignored.
+    pub fn push(
+        &self,
+        type_of: PopupType,
+        node_ref: NodeRef,
+        on_close: Callback<()>,
+        opened_at: f64,
+    ) -> PopupHandle {
+        let handle = {
+            let mut inner = self.inner.borrow_mut();
+
+            let handle = PopupHandle(inner.next_id);
+            inner.next_id += 1;
+
+            inner.entries.push(StackEntry {
+                handle,
+                type_of,
+                node_ref,
+                on_close,
+                opened_at,
+            });
+
+            handle
+        };
+
+        self.changed();
+
+        handle
+    }
+
+    /// Updates the popup after its properties changed. Keeps its place in the stack.
+    pub fn update(&self, handle: PopupHandle, type_of: PopupType, on_close: Callback<()>) {
+        let mut inner = self.inner.borrow_mut();
+
+        if let Some(entry) = inner.entries.iter_mut().find(|v| v.handle == handle) {
+            entry.type_of = type_of;
+            entry.on_close = on_close;
+        }
+    }
+
+    /// Removes the popup without calling its `on_close`. Called by [`Popup`](super::Popup) when it's destroyed.
+    pub fn remove(&self, handle: PopupHandle) {
+        self.inner
+            .borrow_mut()
+            .entries
+            .retain(|v| v.handle != handle);
+
+        self.changed();
+    }
+
+    pub fn top(&self) -> Option<PopupHandle> {
+        self.inner.borrow().entries.last().map(|v| v.handle)
+    }
+
+    pub fn is_topmost(&self, handle: PopupHandle) -> bool {
+        self.top() == Some(handle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.borrow().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().entries.is_empty()
+    }
+
+    /// z-index for the popup based on its place in the stack.
+    pub fn z_index(&self, handle: PopupHandle) -> i32 {
+        let position = self
+            .inner
+            .borrow()
+            .entries
+            .iter()
+            .position(|v| v.handle == handle)
+            .unwrap_or_default();
+
+        POPUP_BASE_Z_INDEX + position as i32 * POPUP_Z_INDEX_STEP
+    }
+
+    /// Asks the popup to close by calling its `on_close`.
+    ///
+    /// The popup stays in the stack until its owner stops rendering it.
+    pub fn close(&self, handle: PopupHandle) {
+        let on_close = self
+            .inner
+            .borrow()
+            .entries
+            .iter()
+            .find(|v| v.handle == handle)
+            .map(|v| v.on_close.clone());
+
+        // Emitted after the borrow ends since the callback may touch the stack.
+        if let Some(on_close) = on_close {
+            on_close.emit(());
+        }
+    }
+
+    /// Closes the topmost popup.
+    pub fn close_top(&self) {
+        if let Some(handle) = self.top() {
+            self.close(handle);
+        }
+    }
+
+    /// Closes every popup, starting from the top.
+    pub fn close_all(&self) {
+        let callbacks = self
+            .inner
+            .borrow()
+            .entries
+            .iter()
+            .rev()
+            .map(|v| v.on_close.clone())
+            .collect::<Vec<_>>();
+
+        for on_close in callbacks {
+            on_close.emit(());
+        }
+    }
+
+    fn on_click(&self, event: &MouseEvent) {
+        let top = self.inner.borrow().entries.last().map(|v| {
+            (
+                v.type_of,
+                v.node_ref.clone(),
+                v.opened_at,
+                v.on_close.clone(),
+            )
+        });
+
+        if let Some((type_of, node_ref, opened_at, on_close)) = top {
+            handle_outside_click(type_of, &node_ref, opened_at, &on_close, event);
+        }
+    }
+
+    fn on_keydown(&self, event: &KeyboardEvent) {
+        let top = self
+            .inner
+            .borrow()
+            .entries
+            .last()
+            .map(|v| (v.node_ref.clone(), v.on_close.clone()));
+
+        if let Some((node_ref, on_close)) = top {
+            handle_keydown(&node_ref, &on_close, event);
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PopupStackProviderProps {
+    pub children: Children,
+}
+
+#[function_component(PopupStackProvider)]
+pub fn _popup_stack_provider(props: &PopupStackProviderProps) -> Html {
+    let force_update = use_force_update();
+
+    let stack = use_state(move || {
+        let stack = PopupStack::default();
+
+        stack.inner.borrow_mut().on_change =
+            Some(Callback::from(move |_| force_update.force_update()));
+
+        stack
+    });
+
+    use_effect_with((*stack).clone(), |stack| {
+        let on_click = {
+            let stack = stack.clone();
+
+            Closure::wrap(Box::new(move |event: MouseEvent| {
+                stack.on_click(&event);
+            }) as Box<dyn FnMut(MouseEvent)>)
+        };
+
+        let on_keydown = {
+            let stack = stack.clone();
+
+            Closure::wrap(Box::new(move |event: KeyboardEvent| {
+                stack.on_keydown(&event);
+            }) as Box<dyn FnMut(KeyboardEvent)>)
+        };
+
+        let _ = body().add_event_listener_with_callback("click", on_click.as_ref().unchecked_ref());
+        let _ = document()
+            .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref());
+
+        move || {
+            let _ = body()
+                .remove_event_listener_with_callback("click", on_click.as_ref().unchecked_ref());
+            let _ = document().remove_event_listener_with_callback(
+                "keydown",
+                on_keydown.as_ref().unchecked_ref(),
+            );
+        }
+    });
+
+    html! {
+        <ContextProvider<PopupStack> context={ stack.snapshot() }>
+            { for props.children.iter() }
+        </ContextProvider<PopupStack>>
+    }
+}
+
+/// The stack from the closest [`PopupStackProvider`].
+#[hook]
+pub fn use_popup_stack() -> Option<PopupStack> {
+    use_context::<PopupStack>()
+}
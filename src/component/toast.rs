@@ -0,0 +1,437 @@
+//! Transient notifications. Wrap the app in a [`ToastProvider`] and push them with [`use_toast`].
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use gloo_timers::callback::Timeout;
+use gloo_utils::{body, window};
+use yew::prelude::*;
+use yew_hooks::use_list;
+
+use crate::api::ApiErrorResponse;
+
+static NEXT_TOAST_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Default number of toasts displayed at once. The rest wait until there's room.
+pub static DEFAULT_MAX_VISIBLE_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    /// Milliseconds before it's dismissed. Errors stay longer so they can be read.
+    pub fn default_timeout(self) -> u32 {
+        match self {
+            Self::Info | Self::Success => 5_000,
+            Self::Warning => 8_000,
+            Self::Error => 12_000,
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Self::Info => "text-bg-info",
+            Self::Success => "text-bg-success",
+            Self::Warning => "text-bg-warning",
+            Self::Error => "text-bg-danger",
+        }
+    }
+
+    /// Warnings and errors interrupt screen readers, the others wait.
+    fn role(self) -> &'static str {
+        match self {
+            Self::Info | Self::Success => "status",
+            Self::Warning | Self::Error => "alert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToastButton {
+    pub label: AttrValue,
+    pub on_click: Callback<()>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub level: ToastLevel,
+    pub title: Option<AttrValue>,
+    pub message: AttrValue,
+    /// Dismisses the toast once clicked.
+    pub action: Option<ToastButton>,
+    /// Milliseconds before it's dismissed. `None` stays until closed.
+    pub timeout: Option<u32>,
+}
+
+impl Toast {
+    pub fn new(level: ToastLevel, message: impl Into<AttrValue>) -> Self {
+        Self {
+            level,
+            title: None,
+            message: message.into(),
+            action: None,
+            timeout: Some(level.default_timeout()),
+        }
+    }
+
+    pub fn info(message: impl Into<AttrValue>) -> Self {
+        Self::new(ToastLevel::Info, message)
+    }
+
+    pub fn success(message: impl Into<AttrValue>) -> Self {
+        Self::new(ToastLevel::Success, message)
+    }
+
+    pub fn warning(message: impl Into<AttrValue>) -> Self {
+        Self::new(ToastLevel::Warning, message)
+    }
+
+    pub fn error(message: impl Into<AttrValue>) -> Self {
+        Self::new(ToastLevel::Error, message)
+    }
+
+    pub fn with_title(mut self, value: impl Into<AttrValue>) -> Self {
+        self.title = Some(value.into());
+        self
+    }
+
+    pub fn with_action(mut self, label: impl Into<AttrValue>, on_click: Callback<()>) -> Self {
+        self.action = Some(ToastButton {
+            label: label.into(),
+            on_click,
+        });
+        self
+    }
+
+    pub fn with_timeout(mut self, millis: u32) -> Self {
+        self.timeout = Some(millis);
+        self
+    }
+
+    /// Stays until the user closes it.
+    pub fn persistent(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+}
+
+impl From<&ApiErrorResponse> for Toast {
+    fn from(value: &ApiErrorResponse) -> Self {
+        Self::error(value.description.clone())
+    }
+}
+
+impl From<ApiErrorResponse> for Toast {
+    fn from(value: ApiErrorResponse) -> Self {
+        Self::error(value.description)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ToastId(usize);
+
+#[derive(Clone, PartialEq)]
+struct ToastEntry {
+    id: ToastId,
+    toast: Toast,
+}
+
+enum ToastMessage {
+    Push(ToastEntry),
+    Dismiss(ToastId),
+    Clear,
+}
+
+#[derive(Clone, PartialEq)]
+struct ToastContext {
+    send: Callback<ToastMessage>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastProviderProps {
+    pub children: Children,
+
+    #[prop_or(DEFAULT_MAX_VISIBLE_TOASTS)]
+    pub max_visible: usize,
+
+    /// Positions the container. Defaults to the bottom right corner.
+    #[prop_or(classes!("bottom-0", "end-0"))]
+    pub classes: Classes,
+}
+
+/// Displays the toasts pushed through [`use_toast`], newest at the bottom.
+#[function_component(ToastProvider)]
+pub fn _toast_provider(props: &ToastProviderProps) -> Html {
+    let toasts = use_list(Vec::<ToastEntry>::new());
+
+    let context = {
+        let toasts = toasts.clone();
+
+        use_memo((), move |_| ToastContext {
+            send: Callback::from(move |msg| match msg {
+                ToastMessage::Push(entry) => toasts.push(entry),
+                ToastMessage::Dismiss(id) => toasts.retain(|v| v.id != id),
+                ToastMessage::Clear => toasts.clear(),
+            }),
+        })
+    };
+
+    let visible = toasts
+        .current()
+        .iter()
+        .take(props.max_visible)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let container = html! {
+        <div class={ classes!("toast-container", "position-fixed", "p-3", props.classes.clone()) }>
+            {
+                for visible.into_iter().map(|entry| {
+                    let on_dismiss = {
+                        let toasts = toasts.clone();
+                        let id = entry.id;
+
+                        Callback::from(move |_| toasts.retain(|v| v.id != id))
+                    };
+
+                    html! {
+                        <ToastItem key={ entry.id.0 } toast={ entry.toast } {on_dismiss} />
+                    }
+                })
+            }
+        </div>
+    };
+
+    html! {
+        <ContextProvider<ToastContext> context={ (*context).clone() }>
+            { for props.children.iter() }
+            { create_portal(container, body().into()) }
+        </ContextProvider<ToastContext>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ToastItemProps {
+    toast: Toast,
+    on_dismiss: Callback<()>,
+}
+
+/// Remaining time of a toast which pauses while hovered.
+struct DismissTimer {
+    timeout: Option<Timeout>,
+    remaining: f64,
+    started_at: f64,
+}
+
+impl DismissTimer {
+    fn start(&mut self, on_dismiss: Callback<()>) {
+        self.started_at = now();
+        self.timeout = Some(Timeout::new(self.remaining as u32, move || {
+            on_dismiss.emit(())
+        }));
+    }
+
+    fn pause(&mut self) {
+        if self.timeout.take().is_some() {
+            self.remaining = (self.remaining - (now() - self.started_at)).max(0.0);
+        }
+    }
+}
+
+#[function_component(ToastItem)]
+fn _toast_item(props: &ToastItemProps) -> Html {
+    let timer = use_mut_ref(|| Option::<DismissTimer>::None);
+
+    // Only starts once displayed so queued toasts get their full time.
+    {
+        let timer = timer.clone();
+        let on_dismiss = props.on_dismiss.clone();
+
+        use_effect_with(props.toast.timeout, move |timeout| {
+            if let Some(timeout) = *timeout {
+                let mut value = DismissTimer {
+                    timeout: None,
+                    remaining: timeout as f64,
+                    started_at: 0.0,
+                };
+
+                value.start(on_dismiss);
+
+                *timer.borrow_mut() = Some(value);
+            }
+
+            move || {
+                timer.borrow_mut().take();
+            }
+        });
+    }
+
+    let on_mouse_enter = pause_callback(&timer);
+    let on_focus_in = pause_callback(&timer);
+
+    let on_mouse_leave = resume_callback(&timer, &props.on_dismiss);
+    let on_focus_out = resume_callback(&timer, &props.on_dismiss);
+
+    let toast = &props.toast;
+
+    let action = toast.action.as_ref().map(|action| {
+        let on_click = {
+            let action = action.on_click.clone();
+            let on_dismiss = props.on_dismiss.clone();
+
+            Callback::from(move |_: MouseEvent| {
+                action.emit(());
+                on_dismiss.emit(());
+            })
+        };
+
+        html! {
+            <button type="button" class="btn btn-sm btn-light ms-auto" onclick={ on_click }>
+                { action.label.clone() }
+            </button>
+        }
+    });
+
+    let close = html! {
+        <button
+            type="button"
+            class="btn-close btn-close-white ms-2"
+            aria-label="Close"
+            onclick={ props.on_dismiss.reform(|_: MouseEvent| ()) }
+        />
+    };
+
+    html! {
+        <div
+            class={ classes!("toast", "show", toast.level.class()) }
+            role={ toast.level.role() }
+            aria-atomic="true"
+            onmouseenter={ on_mouse_enter }
+            onmouseleave={ on_mouse_leave }
+            onfocusin={ on_focus_in }
+            onfocusout={ on_focus_out }
+        >
+            {
+                if let Some(title) = toast.title.clone() {
+                    html! {
+                        <>
+                            <div class="toast-header">
+                                <strong class="me-auto">{ title }</strong>
+                                { close.clone() }
+                            </div>
+                            <div class="toast-body d-flex align-items-center">
+                                { toast.message.clone() }
+                                { action }
+                            </div>
+                        </>
+                    }
+                } else {
+                    html! {
+                        <div class="toast-body d-flex align-items-center">
+                            <span class="me-auto">{ toast.message.clone() }</span>
+                            { action }
+                            { close }
+                        </div>
+                    }
+                }
+            }
+        </div>
+    }
+}
+
+fn pause_callback<E>(timer: &Rc<RefCell<Option<DismissTimer>>>) -> Callback<E> {
+    let timer = timer.clone();
+
+    Callback::from(move |_| {
+        if let Some(timer) = timer.borrow_mut().as_mut() {
+            timer.pause();
+        }
+    })
+}
+
+fn resume_callback<E>(
+    timer: &Rc<RefCell<Option<DismissTimer>>>,
+    on_dismiss: &Callback<()>,
+) -> Callback<E> {
+    let timer = timer.clone();
+    let on_dismiss = on_dismiss.clone();
+
+    Callback::from(move |_| {
+        if let Some(timer) = timer.borrow_mut().as_mut() {
+            if timer.timeout.is_none() {
+                timer.start(on_dismiss.clone());
+            }
+        }
+    })
+}
+
+fn now() -> f64 {
+    window().performance().map(|v| v.now()).unwrap_or_default()
+}
+
+#[derive(Clone, PartialEq)]
+pub struct UseToastHandle {
+    context: Option<ToastContext>,
+}
+
+impl UseToastHandle {
+    /// Displays the toast. Returns its id so it can be dismissed early.
+    pub fn push(&self, toast: impl Into<Toast>) -> ToastId {
+        let id = ToastId(NEXT_TOAST_ID.fetch_add(1, Ordering::Relaxed));
+
+        if let Some(context) = self.context.as_ref() {
+            context.send.emit(ToastMessage::Push(ToastEntry {
+                id,
+                toast: toast.into(),
+            }));
+        } else {
+            log::error!("use_toast called outside of a ToastProvider");
+        }
+
+        id
+    }
+
+    pub fn info(&self, message: impl Into<AttrValue>) -> ToastId {
+        self.push(Toast::info(message))
+    }
+
+    pub fn success(&self, message: impl Into<AttrValue>) -> ToastId {
+        self.push(Toast::success(message))
+    }
+
+    pub fn warning(&self, message: impl Into<AttrValue>) -> ToastId {
+        self.push(Toast::warning(message))
+    }
+
+    pub fn error(&self, message: impl Into<AttrValue>) -> ToastId {
+        self.push(Toast::error(message))
+    }
+
+    pub fn dismiss(&self, id: ToastId) {
+        if let Some(context) = self.context.as_ref() {
+            context.send.emit(ToastMessage::Dismiss(id));
+        }
+    }
+
+    /// Dismisses every toast, including the ones waiting to be displayed.
+    pub fn clear(&self) {
+        if let Some(context) = self.context.as_ref() {
+            context.send.emit(ToastMessage::Clear);
+        }
+    }
+}
+
+#[hook]
+pub fn use_toast() -> UseToastHandle {
+    UseToastHandle {
+        context: use_context::<ToastContext>(),
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::FromRow;
+
+use super::{Filter, ListQuery, Pagination, Sort};
+use crate::{
+    api::librarian::{GetSearchQuery, PublicBook},
+    BookId, BookPersonId, Language, PersonId, Source, ThumbnailStore,
+};
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct BookRow {
+    pub id: BookId,
+
+    pub source: Source,
+
+    pub title: Option<String>,
+    pub clean_title: Option<String>,
+    pub description: Option<String>,
+    pub rating: f64,
+
+    pub thumb_url: ThumbnailStore,
+
+    pub isbn_10: Option<String>,
+    pub isbn_13: Option<String>,
+
+    pub display_person_id: Option<PersonId>,
+    pub publisher: Option<String>,
+
+    pub is_public: bool,
+    pub edition_count: i64,
+
+    pub available_at: Option<NaiveDate>,
+    pub language: Language,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl BookRow {
+    /// Non-deleted books matching the search query, most recently updated first.
+    pub fn list_query(query: &GetSearchQuery) -> ListQuery {
+        let mut list = ListQuery::new("book")
+            .filter(Filter::IsNull("deleted_at"))
+            .sort(Sort::desc("updated_at"))
+            .paginate(Pagination::new(query.offset, query.limit));
+
+        if !query.view_private {
+            list = list.filter(Filter::eq("is_public", true));
+        }
+
+        if query.with_isbn {
+            list = list.filter(Filter::Any(vec![
+                Filter::IsNotNull("isbn_13"),
+                Filter::IsNotNull("isbn_10"),
+            ]));
+        }
+
+        let search = query.query.trim();
+
+        if !search.is_empty() {
+            list = list.filter(Filter::contains(&["title", "clean_title"], search));
+        }
+
+        list
+    }
+
+    pub fn isbns(&self) -> Option<Vec<String>> {
+        let isbns = self
+            .isbn_13
+            .iter()
+            .chain(self.isbn_10.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Some(isbns).filter(|v| !v.is_empty())
+    }
+
+    /// `author_ids` come from the `book_person` table.
+    pub fn into_public(self, author_ids: Vec<PersonId>) -> PublicBook {
+        PublicBook {
+            id: *self.id as usize,
+            isbns: self.isbns(),
+
+            title: self.title,
+            clean_title: self.clean_title,
+            description: self.description,
+            rating: self.rating,
+            thumb_url: self.thumb_url.into_value(),
+            display_author_id: self.display_person_id.map(|v| *v as usize),
+            publisher: self.publisher,
+            author_ids: author_ids.into_iter().map(|v| *v as usize).collect(),
+            is_public: self.is_public,
+            edition_count: self.edition_count as usize,
+            available_at: self.available_at,
+            language: self.language.id(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            deleted_at: self.deleted_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct BookPersonRow {
+    pub id: BookPersonId,
+
+    pub book_id: BookId,
+    pub person_id: PersonId,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::{ImageId, ImageIdType, ThumbnailStore};
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ImageRow {
+    pub id: ImageId,
+
+    pub path: ThumbnailStore,
+
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct ImageLinkRow {
+    pub image_id: ImageId,
+
+    /// The book or person the image belongs to.
+    pub link: ImageIdType,
+}
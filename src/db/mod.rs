@@ -0,0 +1,20 @@
+//! Row models and list queries for the schema shared by the librarian and reader servers.
+//!
+//! Run [`MIGRATOR`] against the SQLite database before using them.
+
+use sqlx::migrate::Migrator;
+
+mod book;
+mod image;
+mod person;
+mod query;
+mod tag;
+
+pub use book::*;
+pub use image::*;
+pub use person::*;
+pub use query::*;
+pub use tag::*;
+
+/// Embedded migrations from `/migrations`.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
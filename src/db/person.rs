@@ -0,0 +1,67 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::FromRow;
+
+use super::{Filter, ListQuery, Pagination, Sort};
+use crate::{
+    api::librarian::{GetSearchQuery, PublicAuthor},
+    PersonId, Source, ThumbnailStore,
+};
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct PersonRow {
+    pub id: PersonId,
+
+    pub source: Source,
+
+    pub name: String,
+    pub description: Option<String>,
+    pub birth_date: Option<NaiveDate>,
+
+    pub thumb_url: ThumbnailStore,
+
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PersonRow {
+    /// Non-deleted people whose name contains the search query, sorted by name.
+    ///
+    /// `view_private` and `with_isbn` only apply to books.
+    pub fn list_query(query: &GetSearchQuery) -> ListQuery {
+        let mut list = ListQuery::new("person")
+            .filter(Filter::IsNull("deleted_at"))
+            .sort(Sort::asc("name"))
+            .paginate(Pagination::new(query.offset, query.limit));
+
+        let search = query.query.trim();
+
+        if !search.is_empty() {
+            list = list.filter(Filter::contains(&["name"], search));
+        }
+
+        list
+    }
+
+    /// `other_names` come from the `person_alt` table.
+    pub fn into_public(self, other_names: Vec<String>) -> PublicAuthor {
+        PublicAuthor {
+            id: *self.id as usize,
+            name: self.name,
+            description: self.description,
+            birth_date: self.birth_date,
+            thumb_url: self.thumb_url.into_value(),
+            other_names,
+            updated_at: self.updated_at,
+            created_at: self.created_at,
+            deleted_at: self.deleted_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct PersonAltRow {
+    pub person_id: PersonId,
+
+    pub name: String,
+}
@@ -0,0 +1,472 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Encode, QueryBuilder, Type};
+
+use crate::api::QueryListResponse;
+
+pub static DEFAULT_LIST_LIMIT: usize = 25;
+pub static MAX_LIST_LIMIT: usize = 100;
+
+/// A value bound as a query parameter. Never written into the SQL itself.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bool(bool),
+    DateTime(DateTime<Utc>),
+}
+
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<DateTime<Utc>> for FilterValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self::DateTime(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl CompareOp {
+    fn as_sql(self) -> &'static str {
+        match self {
+            Self::Eq => " = ",
+            Self::NotEq => " != ",
+            Self::Lt => " < ",
+            Self::Lte => " <= ",
+            Self::Gt => " > ",
+            Self::Gte => " >= ",
+        }
+    }
+}
+
+/// A `WHERE` condition.
+///
+/// Column names are `&'static str` so they can only come from the handler, never from the request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare(&'static str, CompareOp, FilterValue),
+    In(&'static str, Vec<FilterValue>),
+    IsNull(&'static str),
+    IsNotNull(&'static str),
+    /// Case insensitive substring match on any of the columns. `%` and `_` are matched literally.
+    Contains(&'static [&'static str], String),
+    Any(Vec<Filter>),
+    All(Vec<Filter>),
+}
+
+impl Filter {
+    pub fn eq(column: &'static str, value: impl Into<FilterValue>) -> Self {
+        Self::Compare(column, CompareOp::Eq, value.into())
+    }
+
+    pub fn not_eq(column: &'static str, value: impl Into<FilterValue>) -> Self {
+        Self::Compare(column, CompareOp::NotEq, value.into())
+    }
+
+    pub fn lt(column: &'static str, value: impl Into<FilterValue>) -> Self {
+        Self::Compare(column, CompareOp::Lt, value.into())
+    }
+
+    pub fn gt(column: &'static str, value: impl Into<FilterValue>) -> Self {
+        Self::Compare(column, CompareOp::Gt, value.into())
+    }
+
+    pub fn contains(columns: &'static [&'static str], value: impl Into<String>) -> Self {
+        Self::Contains(columns, value.into())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => " ASC",
+            Self::Desc => " DESC",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: &'static str,
+    pub order: SortOrder,
+}
+
+impl Sort {
+    pub fn asc(column: &'static str) -> Self {
+        Self {
+            column,
+            order: SortOrder::Asc,
+        }
+    }
+
+    pub fn desc(column: &'static str) -> Self {
+        Self {
+            column,
+            order: SortOrder::Desc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Pagination {
+    /// Uses [`DEFAULT_LIST_LIMIT`] if no limit is given and caps it at [`MAX_LIST_LIMIT`].
+    pub fn new(offset: Option<usize>, limit: Option<usize>) -> Self {
+        Self {
+            offset: offset.unwrap_or_default(),
+            limit: limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT),
+        }
+    }
+
+    pub fn into_response<V>(self, total: i64, items: Vec<V>) -> QueryListResponse<V> {
+        QueryListResponse {
+            offset: self.offset,
+            limit: self.limit,
+            total: total.max(0) as usize,
+            items,
+        }
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+/// Builds the page and `COUNT(*)` queries for a list endpoint.
+///
+/// ```ignore
+/// let list = ListQuery::new("book")
+///     .filter(Filter::eq("is_public", true))
+///     .sort(Sort::desc("updated_at"))
+///     .paginate(Pagination::new(query.offset, query.limit));
+///
+/// let total: i64 = list.count().build_query_scalar().fetch_one(db).await?;
+/// let items: Vec<BookRow> = list.select().build_query_as().fetch_all(db).await?;
+///
+/// let resp = list.pagination.into_response(total, items);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery {
+    pub table: &'static str,
+    pub columns: &'static str,
+    /// Appended to the sort so that rows with equal sort values keep the same order between pages.
+    pub id_column: &'static str,
+
+    pub filters: Vec<Filter>,
+    pub sort: Vec<Sort>,
+    pub pagination: Pagination,
+}
+
+impl ListQuery {
+    pub fn new(table: &'static str) -> Self {
+        Self {
+            table,
+            columns: "*",
+            id_column: "id",
+            filters: Vec::new(),
+            sort: Vec::new(),
+            pagination: Pagination::default(),
+        }
+    }
+
+    pub fn columns(mut self, columns: &'static str) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn sort(mut self, sort: Sort) -> Self {
+        self.sort.push(sort);
+        self
+    }
+
+    pub fn paginate(mut self, pagination: Pagination) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    /// `SELECT {columns} FROM {table} WHERE .. ORDER BY .. LIMIT .. OFFSET ..`
+    pub fn select<'q, DB>(&self) -> QueryBuilder<'q, DB>
+    where
+        DB: Database,
+        i64: Encode<'q, DB> + Type<DB>,
+        f64: Encode<'q, DB> + Type<DB>,
+        String: Encode<'q, DB> + Type<DB>,
+        bool: Encode<'q, DB> + Type<DB>,
+        DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+    {
+        let mut builder = QueryBuilder::new(format!("SELECT {} FROM {}", self.columns, self.table));
+
+        self.push_where(&mut builder);
+
+        builder.push(" ORDER BY ");
+
+        for sort in self.sort.iter().filter(|v| v.column != self.id_column) {
+            builder.push(sort.column);
+            builder.push(sort.order.as_sql());
+            builder.push(", ");
+        }
+
+        builder.push(self.id_column);
+
+        // Always last. Keeps the direction it was given if the id was sorted on explicitly.
+        let id_order = self
+            .sort
+            .iter()
+            .find(|v| v.column == self.id_column)
+            .map(|v| v.order)
+            .unwrap_or_default();
+
+        builder.push(id_order.as_sql());
+
+        self.push_pagination(&mut builder);
+
+        builder
+    }
+
+    /// `SELECT COUNT(*) FROM {table} WHERE ..`
+    pub fn count<'q, DB>(&self) -> QueryBuilder<'q, DB>
+    where
+        DB: Database,
+        i64: Encode<'q, DB> + Type<DB>,
+        f64: Encode<'q, DB> + Type<DB>,
+        String: Encode<'q, DB> + Type<DB>,
+        bool: Encode<'q, DB> + Type<DB>,
+        DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+    {
+        let mut builder = QueryBuilder::new(format!("SELECT COUNT(*) FROM {}", self.table));
+
+        self.push_where(&mut builder);
+
+        builder
+    }
+
+    fn push_where<'q, DB>(&self, builder: &mut QueryBuilder<'q, DB>)
+    where
+        DB: Database,
+        i64: Encode<'q, DB> + Type<DB>,
+        f64: Encode<'q, DB> + Type<DB>,
+        String: Encode<'q, DB> + Type<DB>,
+        bool: Encode<'q, DB> + Type<DB>,
+        DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+    {
+        if self.filters.is_empty() {
+            return;
+        }
+
+        builder.push(" WHERE ");
+
+        push_filters(builder, &self.filters, " AND ");
+    }
+
+    fn push_pagination<'q, DB>(&self, builder: &mut QueryBuilder<'q, DB>)
+    where
+        DB: Database,
+        i64: Encode<'q, DB> + Type<DB>,
+    {
+        // Clamped so a huge offset doesn't wrap around to a negative one.
+        builder.push(" LIMIT ");
+        builder.push_bind(i64::try_from(self.pagination.limit).unwrap_or(i64::MAX));
+        builder.push(" OFFSET ");
+        builder.push_bind(i64::try_from(self.pagination.offset).unwrap_or(i64::MAX));
+    }
+}
+
+fn push_filters<'q, DB>(builder: &mut QueryBuilder<'q, DB>, filters: &[Filter], separator: &str)
+where
+    DB: Database,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    for (i, filter) in filters.iter().enumerate() {
+        if i != 0 {
+            builder.push(separator);
+        }
+
+        push_filter(builder, filter);
+    }
+}
+
+fn push_filter<'q, DB>(builder: &mut QueryBuilder<'q, DB>, filter: &Filter)
+where
+    DB: Database,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    match filter {
+        Filter::Compare(column, op, value) => {
+            builder.push(column);
+            builder.push(op.as_sql());
+            push_value(builder, value);
+        }
+
+        // An empty list would be invalid SQL and could never match anyway.
+        Filter::In(_, values) if values.is_empty() => {
+            builder.push("1 = 0");
+        }
+
+        Filter::In(column, values) => {
+            builder.push(column);
+            builder.push(" IN (");
+
+            for (i, value) in values.iter().enumerate() {
+                if i != 0 {
+                    builder.push(", ");
+                }
+
+                push_value(builder, value);
+            }
+
+            builder.push(")");
+        }
+
+        Filter::IsNull(column) => {
+            builder.push(column);
+            builder.push(" IS NULL");
+        }
+
+        Filter::IsNotNull(column) => {
+            builder.push(column);
+            builder.push(" IS NOT NULL");
+        }
+
+        Filter::Contains([], _) => {
+            builder.push("1 = 0");
+        }
+
+        Filter::Contains(columns, value) => {
+            let pattern = format!("%{}%", escape_like(value));
+
+            builder.push("(");
+
+            for (i, column) in columns.iter().enumerate() {
+                if i != 0 {
+                    builder.push(" OR ");
+                }
+
+                builder.push("LOWER(");
+                builder.push(column);
+                builder.push(") LIKE LOWER(");
+                builder.push_bind(pattern.clone());
+                builder.push(") ESCAPE '\\'");
+            }
+
+            builder.push(")");
+        }
+
+        Filter::Any(filters) | Filter::All(filters) if filters.is_empty() => {
+            // Matches SQL's behaviour for an empty OR (false) and AND (true).
+            builder.push(if matches!(filter, Filter::Any(_)) {
+                "1 = 0"
+            } else {
+                "1 = 1"
+            });
+        }
+
+        Filter::Any(filters) => {
+            builder.push("(");
+            push_filters(builder, filters, " OR ");
+            builder.push(")");
+        }
+
+        Filter::All(filters) => {
+            builder.push("(");
+            push_filters(builder, filters, " AND ");
+            builder.push(")");
+        }
+    }
+}
+
+fn push_value<'q, DB>(builder: &mut QueryBuilder<'q, DB>, value: &FilterValue)
+where
+    DB: Database,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    match value.clone() {
+        FilterValue::Int(v) => builder.push_bind(v),
+        FilterValue::Float(v) => builder.push_bind(v),
+        FilterValue::Text(v) => builder.push_bind(v),
+        FilterValue::Bool(v) => builder.push_bind(v),
+        FilterValue::DateTime(v) => builder.push_bind(v),
+    };
+}
+
+/// Escapes `\`, `%` and `_` for use with `LIKE .. ESCAPE '\'`.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
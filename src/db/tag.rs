@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::{BookId, BookTagId, TagId};
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct TagRow {
+    pub id: TagId,
+
+    pub name: String,
+    pub type_of: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct BookTagRow {
+    pub id: BookTagId,
+
+    pub book_id: BookId,
+    pub tag_id: TagId,
+
+    /// Position of the tag in the book's list.
+    pub idx: i64,
+
+    pub created_at: DateTime<Utc>,
+}
@@ -1,13 +1,17 @@
-use std::{num::ParseIntError, sync::PoisonError};
+use std::{borrow::Cow, fmt::Write, num::ParseIntError, panic::Location, sync::PoisonError};
 
+use serde::{Serialize, Serializer};
 use thiserror::Error as ThisError;
 
+use roxmltree::Error as XmlError;
 use serde::de::value::Error as SerdeValueError;
 use serde_json::Error as SerdeJsonError;
 use serde_urlencoded::ser::Error as SerdeUrlEncodedError;
 use std::io::Error as IoError;
 use std::time::SystemTimeError;
 
+use crate::api::{ApiErrorResponse, ErrorCodeResponse, WrappingResponse};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, ThisError)]
@@ -30,9 +34,154 @@ pub enum Error {
     SystemTime(#[from] SystemTimeError),
     #[error("Parse Int Error: {0}")]
     ParseInt(#[from] ParseIntError),
+    #[error("XML Error: {0}")]
+    Xml(#[from] XmlError),
 
     #[error("Missing ':' from Source")]
     SourceSplit,
+
+    #[error("Invalid Id: {0}")]
+    InvalidId(String),
+    #[error("Invalid ISBN: {0}")]
+    InvalidIsbn(String),
+    #[error("Invalid Image: {0}")]
+    InvalidImage(String),
+    #[error("Invalid OPF: {0}")]
+    InvalidOpf(String),
+    #[error("Invalid Sync Token: {0}")]
+    InvalidSyncToken(String),
+
+    /// Added through [`ResultExt::context`].
+    #[error("{message}")]
+    Context {
+        message: Cow<'static, str>,
+        location: &'static Location<'static>,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Wraps the error with a message describing what we were doing.
+    #[track_caller]
+    pub fn context<C: Into<Cow<'static, str>>>(self, message: C) -> Self {
+        Self::Context {
+            message: message.into(),
+            location: Location::caller(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error underneath any context.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+
+    /// Renders the context messages and their locations followed by the causes.
+    ///
+    /// ```text
+    /// loading thumbnail for book 12 (src/image.rs:40:10)
+    ///
+    /// Caused by:
+    ///     0: reading file (src/file.rs:12:5)
+    ///     1: IO Error: No such file or directory
+    /// ```
+    pub fn display_chain(&self) -> String {
+        let mut lines = Vec::new();
+
+        let mut current = self;
+
+        while let Self::Context {
+            message,
+            location,
+            source,
+        } = current
+        {
+            lines.push(format!("{message} ({location})"));
+            current = source;
+        }
+
+        lines.push(current.to_string());
+
+        let mut cause = std::error::Error::source(current);
+
+        while let Some(error) = cause {
+            let line = error.to_string();
+
+            // Our variants already include their source in the message.
+            if !lines.last().map(|v| v.ends_with(&line)).unwrap_or_default() {
+                lines.push(line);
+            }
+
+            cause = error.source();
+        }
+
+        let mut compiled = lines.remove(0);
+
+        if !lines.is_empty() {
+            compiled.push_str("\n\nCaused by:");
+
+            for (i, line) in lines.iter().enumerate() {
+                let _ = write!(compiled, "\n    {i}: {line}");
+            }
+        }
+
+        compiled
+    }
+
+    pub fn code(&self) -> ErrorCodeResponse {
+        match self {
+            Self::Context { source, .. } => source.code(),
+
+            Self::Poisoned | Self::Io(_) | Self::SystemTime(_) => ErrorCodeResponse::Internal,
+
+            Self::SerdeValue(_) | Self::SerdeUrlEncoded(_) | Self::SerdeJson(_) => {
+                ErrorCodeResponse::InvalidData
+            }
+
+            Self::ParseInt(_) | Self::Xml(_) | Self::InvalidOpf(_) | Self::InvalidSyncToken(_) => {
+                ErrorCodeResponse::InvalidData
+            }
+            Self::SourceSplit => ErrorCodeResponse::InvalidSource,
+            Self::InvalidId(_) => ErrorCodeResponse::InvalidId,
+            Self::InvalidIsbn(_) => ErrorCodeResponse::InvalidIsbn,
+            Self::InvalidImage(_) => ErrorCodeResponse::InvalidImage,
+        }
+    }
+
+    /// Description which is safe to send to a client. Doesn't contain any internal details.
+    pub fn public_description(&self) -> &'static str {
+        match self {
+            Self::Context { source, .. } => source.public_description(),
+
+            Self::Poisoned | Self::Io(_) | Self::SystemTime(_) => "Internal Server Error",
+
+            Self::SerdeValue(_) | Self::SerdeUrlEncoded(_) | Self::SerdeJson(_) => {
+                "Unable to read the provided data"
+            }
+
+            Self::ParseInt(_) => "Expected a number",
+            Self::Xml(_) => "Unable to read the provided XML",
+            Self::InvalidOpf(_) => "Invalid OPF metadata",
+            Self::InvalidSyncToken(_) => "Invalid sync token",
+            Self::SourceSplit => "Invalid Source. Expected \"agent:value\"",
+            Self::InvalidId(_) => "Invalid Id",
+            Self::InvalidIsbn(_) => "Invalid ISBN",
+            Self::InvalidImage(_) => "Invalid Image",
+        }
+    }
+
+    /// The error was our fault rather than the client's.
+    pub fn is_internal(&self) -> bool {
+        self.code() == ErrorCodeResponse::Internal
+    }
+
+    /// Converts into a client safe response without logging.
+    pub fn to_api_response(&self) -> ApiErrorResponse {
+        ApiErrorResponse::new_with_code(self.public_description(), self.code())
+    }
 }
 
 impl<V> From<PoisonError<V>> for Error {
@@ -40,3 +189,64 @@ impl<V> From<PoisonError<V>> for Error {
         Self::Poisoned
     }
 }
+
+impl From<Error> for ApiErrorResponse {
+    /// Logs the full error and returns a client safe response.
+    fn from(value: Error) -> Self {
+        if value.is_internal() {
+            log::error!("{}", value.display_chain());
+        } else {
+            log::debug!("{}", value.display_chain());
+        }
+
+        value.to_api_response()
+    }
+}
+
+impl<V> From<Error> for WrappingResponse<V> {
+    fn from(value: Error) -> Self {
+        Self::Error(value.into())
+    }
+}
+
+impl Serialize for Error {
+    /// Serialized as an [`ApiErrorResponse`]. Internal details are left out.
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_api_response().serialize(serializer)
+    }
+}
+
+/// Adds context to any error which can be converted into an [`Error`].
+///
+/// ```ignore
+/// let file = File::open(&path).context("loading thumbnail for book 12")?;
+/// ```
+pub trait ResultExt<T> {
+    #[track_caller]
+    fn context<C: Into<Cow<'static, str>>>(self, message: C) -> Result<T>;
+
+    /// Only creates the message if there was an error.
+    #[track_caller]
+    fn with_context<C: Into<Cow<'static, str>>, F: FnOnce() -> C>(self, func: F) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    #[track_caller]
+    fn context<C: Into<Cow<'static, str>>>(self, message: C) -> Result<T> {
+        match self {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into().context(message)),
+        }
+    }
+
+    #[track_caller]
+    fn with_context<C: Into<Cow<'static, str>>, F: FnOnce() -> C>(self, func: F) -> Result<T> {
+        match self {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into().context(func())),
+        }
+    }
+}
@@ -11,6 +11,8 @@
 pub mod component;
 
 pub mod api;
+#[cfg(feature = "backend")]
+pub mod db;
 pub mod error;
 pub mod specific;
 pub mod util;
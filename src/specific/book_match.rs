@@ -0,0 +1,237 @@
+use chrono::Datelike;
+
+use crate::{
+    api::librarian::{PartialBook, PublicBook},
+    normalize_isbn, normalize_title,
+    util::{normalize_for_comparison, similarity},
+    PersonName,
+};
+
+/// Confidence at which a candidate can be treated as the same book without asking.
+pub const CONFIDENT_MATCH_THRESHOLD: f64 = 0.85;
+
+static TITLE_WEIGHT: f64 = 0.5;
+static AUTHORS_WEIGHT: f64 = 0.3;
+static YEAR_WEIGHT: f64 = 0.2;
+
+/// Highest confidence without a shared ISBN when only one part could be compared. eg. two books titled "Poems"
+static SINGLE_PART_MAX_CONFIDENCE: f64 = 0.75;
+
+/// The parts of a book used for matching.
+///
+/// Books only store author ids so the author names have to be added with [`BookMatchFields::with_authors`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookMatchFields {
+    pub title: Option<String>,
+    pub isbns: Vec<String>,
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+}
+
+impl BookMatchFields {
+    pub fn with_authors(mut self, authors: Vec<String>) -> Self {
+        self.authors = authors;
+        self
+    }
+
+    /// Compares against a candidate. See [`match_book`].
+    pub fn compare(&self, candidate: &Self) -> BookMatch {
+        match_book(self, candidate)
+    }
+}
+
+impl From<&PublicBook> for BookMatchFields {
+    fn from(value: &PublicBook) -> Self {
+        Self {
+            title: value.title.clone().or_else(|| value.clean_title.clone()),
+            isbns: value.isbns.clone().unwrap_or_default(),
+            authors: Vec::new(),
+            year: value.available_at.map(|v| v.year()),
+        }
+    }
+}
+
+impl From<&PartialBook> for BookMatchFields {
+    fn from(value: &PartialBook) -> Self {
+        Self {
+            title: value.title.clone(),
+            isbns: value.isbns.clone().unwrap_or_default(),
+            authors: Vec::new(),
+            year: value.available_at.map(|v| v.year()),
+        }
+    }
+}
+
+/// How much each part of the book matched, from `0.0` to `1.0`.
+///
+/// A part is `None` if either book is missing it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BookMatchBreakdown {
+    /// `1.0` if the books share an ISBN, `0.0` if they don't share any.
+    pub isbn: Option<f64>,
+    pub title: Option<f64>,
+    pub authors: Option<f64>,
+    pub year: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BookMatch {
+    /// From `0.0` to `1.0`.
+    pub confidence: f64,
+    pub breakdown: BookMatchBreakdown,
+}
+
+impl BookMatch {
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= CONFIDENT_MATCH_THRESHOLD
+    }
+}
+
+/// Scores how likely `candidate` is the same book as `local`.
+///
+/// A shared ISBN is close to certain. Different ISBNs only lower the score since they could be other editions.
+pub fn match_book(local: &BookMatchFields, candidate: &BookMatchFields) -> BookMatch {
+    let breakdown = BookMatchBreakdown {
+        isbn: isbn_score(&local.isbns, &candidate.isbns),
+        title: local
+            .title
+            .as_deref()
+            .zip(candidate.title.as_deref())
+            .map(|(l, r)| title_similarity(l, r)),
+        authors: authors_score(&local.authors, &candidate.authors),
+        year: local
+            .year
+            .zip(candidate.year)
+            .map(|(l, r)| year_score(l, r)),
+    };
+
+    let mut total = 0.0;
+    let mut total_weight = 0.0;
+    let mut parts = 0;
+
+    for (score, weight) in [
+        (breakdown.title, TITLE_WEIGHT),
+        (breakdown.authors, AUTHORS_WEIGHT),
+        (breakdown.year, YEAR_WEIGHT),
+    ] {
+        if let Some(score) = score {
+            total += score * weight;
+            total_weight += weight;
+            parts += 1;
+        }
+    }
+
+    let weighted = if total_weight == 0.0 {
+        0.0
+    } else {
+        total / total_weight
+    };
+
+    let confidence = match breakdown.isbn {
+        Some(v) if v >= 1.0 => 0.95 + weighted * 0.05,
+        // Possibly a different edition.
+        Some(_) => weighted * 0.85,
+        None => weighted,
+    };
+
+    let is_shared_isbn = breakdown.isbn.is_some_and(|v| v >= 1.0);
+
+    let confidence = if !is_shared_isbn && parts < 2 {
+        confidence.min(SINGLE_PART_MAX_CONFIDENCE)
+    } else {
+        confidence
+    };
+
+    BookMatch {
+        confidence,
+        breakdown,
+    }
+}
+
+/// Scores every candidate and returns their indexes from best to worst match.
+pub fn rank_book_matches(
+    local: &BookMatchFields,
+    candidates: &[BookMatchFields],
+) -> Vec<(usize, BookMatch)> {
+    let mut ranked = candidates
+        .iter()
+        .map(|v| match_book(local, v))
+        .enumerate()
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|a, b| b.1.confidence.total_cmp(&a.1.confidence));
+
+    ranked
+}
+
+/// Compares two titles after removing series markers, subtitles and edition notes.
+pub fn title_similarity(left: &str, right: &str) -> f64 {
+    let full_left = normalize_for_comparison(left);
+    let full_right = normalize_for_comparison(right);
+
+    if full_left == full_right {
+        return 1.0;
+    }
+
+    let left = normalize_for_comparison(&normalize_title(left));
+    let right = normalize_for_comparison(&normalize_title(right));
+
+    // Also compare the words in any order. eg. "Hobbit, The" vs "The Hobbit"
+    let mut left_words = left.split(' ').collect::<Vec<_>>();
+    let mut right_words = right.split(' ').collect::<Vec<_>>();
+    left_words.sort_unstable();
+    right_words.sort_unstable();
+
+    similarity(&left, &right).max(similarity(&left_words.join(" "), &right_words.join(" ")))
+}
+
+fn isbn_score(left: &[String], right: &[String]) -> Option<f64> {
+    let left = left
+        .iter()
+        .filter_map(|v| normalize_isbn(v))
+        .collect::<Vec<_>>();
+    let right = right
+        .iter()
+        .filter_map(|v| normalize_isbn(v))
+        .collect::<Vec<_>>();
+
+    if left.is_empty() || right.is_empty() {
+        None
+    } else if left.iter().any(|v| right.contains(v)) {
+        Some(1.0)
+    } else {
+        Some(0.0)
+    }
+}
+
+fn authors_score(left: &[String], right: &[String]) -> Option<f64> {
+    if left.is_empty() || right.is_empty() {
+        return None;
+    }
+
+    let right = right
+        .iter()
+        .map(|v| PersonName::parse(v))
+        .collect::<Vec<_>>();
+
+    let total = left
+        .iter()
+        .map(|name| {
+            let name = PersonName::parse(name);
+
+            right.iter().map(|v| name.similarity(v)).fold(0.0, f64::max)
+        })
+        .sum::<f64>();
+
+    Some(total / left.len() as f64)
+}
+
+fn year_score(left: i32, right: i32) -> f64 {
+    match (left - right).abs() {
+        0 => 1.0,
+        1 => 0.8,
+        2 => 0.5,
+        3..=5 => 0.2,
+        _ => 0.0,
+    }
+}
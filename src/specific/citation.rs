@@ -0,0 +1,656 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{
+    api::librarian::{PublicAuthor, PublicBook},
+    error::Result,
+    get_language_code, get_language_marc_code, normalize_isbn, normalize_title, parse_opf_date,
+    sort_title,
+    util::normalize_for_comparison,
+    Language, PersonName,
+};
+
+static BIBTEX_MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// A book and its authors, in the order of [`PublicBook::author_ids`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CitationEntry<'a> {
+    pub book: &'a PublicBook,
+    pub authors: Vec<&'a PublicAuthor>,
+}
+
+impl<'a> CitationEntry<'a> {
+    /// Picks the book's authors out of `authors`. Unknown ids are skipped.
+    pub fn new(book: &'a PublicBook, authors: &'a [PublicAuthor]) -> Self {
+        Self {
+            book,
+            authors: book
+                .author_ids
+                .iter()
+                .filter_map(|id| authors.iter().find(|v| v.id == *id))
+                .collect(),
+        }
+    }
+
+    /// `{family name}{year}{first title word}`, in lowercase ASCII. eg. "tolkien1954hobbit"
+    ///
+    /// Leading articles are skipped. Use [`citation_keys`] when exporting multiple entries so they stay unique.
+    pub fn citation_key(&self) -> String {
+        let mut key = self
+            .authors
+            .first()
+            .map(|v| normalize_for_comparison(&PersonName::parse(&v.name).family).replace(' ', ""))
+            .unwrap_or_default();
+
+        if let Some(date) = self.book.available_at {
+            let _ = write!(key, "{}", date.year());
+        }
+
+        if let Some(title) = self.book.title.as_deref() {
+            let title = normalize_for_comparison(&sort_title(title, self.book.language));
+
+            key.push_str(title.split(' ').next().unwrap_or_default());
+        }
+
+        if key.is_empty() {
+            key = format!("book{}", self.book.id);
+        }
+
+        key
+    }
+
+    fn isbn(&self) -> Option<&str> {
+        self.book.isbns.as_ref()?.first().map(|v| v.as_str())
+    }
+
+    fn author_sort_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.authors
+            .iter()
+            .map(|v| PersonName::parse(&v.name).sort_name())
+    }
+}
+
+/// Citation keys for each entry. Duplicates get a letter suffix. eg. "king1986ita", "king1986itb"
+///
+/// Suffixes continue with "aa", "ab".. after "z" and skip keys which are already used by another entry.
+pub fn citation_keys(entries: &[CitationEntry]) -> Vec<String> {
+    let keys = entries.iter().map(|v| v.citation_key()).collect::<Vec<_>>();
+
+    let mut counts = HashMap::<&str, usize>::new();
+
+    for key in &keys {
+        *counts.entry(key.as_str()).or_default() += 1;
+    }
+
+    let mut used = keys.iter().cloned().collect::<HashSet<_>>();
+    let mut next_index = HashMap::<&str, usize>::new();
+
+    keys.iter()
+        .map(|key| {
+            if counts[key.as_str()] == 1 {
+                return key.clone();
+            }
+
+            let index = next_index.entry(key.as_str()).or_default();
+
+            loop {
+                let value = format!("{key}{}", letter_suffix(*index));
+                *index += 1;
+
+                if used.insert(value.clone()) {
+                    return value;
+                }
+            }
+        })
+        .collect()
+}
+
+/// 0 -> "a", 25 -> "z", 26 -> "aa", 27 -> "ab"
+fn letter_suffix(mut index: usize) -> String {
+    let mut compiled = String::new();
+
+    loop {
+        compiled.push(char::from(b'a' + (index % 26) as u8));
+
+        if index < 26 {
+            break;
+        }
+
+        index = index / 26 - 1;
+    }
+
+    compiled.chars().rev().collect()
+}
+
+/// BibTeX `@book` entries.
+pub fn to_bibtex(entries: &[CitationEntry]) -> String {
+    let mut out = String::new();
+
+    for (entry, key) in entries.iter().zip(citation_keys(entries)) {
+        let book = entry.book;
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "@book{{{key},");
+
+        let mut field = |name: &str, value: &str| {
+            let _ = writeln!(out, "  {name} = {{{}}},", escape_bibtex(value));
+        };
+
+        if let Some(title) = book.title.as_deref() {
+            field("title", title);
+        }
+
+        let authors = entry.author_sort_names().collect::<Vec<_>>();
+
+        if !authors.is_empty() {
+            field("author", &authors.join(" and "));
+        }
+
+        if let Some(publisher) = book.publisher.as_deref() {
+            field("publisher", publisher);
+        }
+
+        if let Some(date) = book.available_at {
+            field("year", &date.year().to_string());
+        }
+
+        if let Some(isbn) = entry.isbn() {
+            field("isbn", isbn);
+        }
+
+        field("language", Language::from(book.language).name());
+
+        if let Some(description) = book.description.as_deref() {
+            field("abstract", description);
+        }
+
+        // Months are macros and aren't wrapped in braces.
+        if let Some(date) = book.available_at {
+            let _ = writeln!(out, "  month = {},", BIBTEX_MONTHS[date.month0() as usize]);
+        }
+
+        out.push_str("}\n");
+    }
+
+    out
+}
+
+/// Escapes characters which have a special meaning in BibTeX.
+pub fn escape_bibtex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '{' | '}' | '%' | '&' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' | '\t' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// RIS records. Lines end with CRLF as the format expects.
+pub fn to_ris(entries: &[CitationEntry]) -> String {
+    let mut out = String::new();
+
+    for (entry, key) in entries.iter().zip(citation_keys(entries)) {
+        let book = entry.book;
+
+        let mut tag = |name: &str, value: &str| {
+            // Values can't span multiple lines.
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            let _ = write!(out, "{name}  - {value}\r\n");
+        };
+
+        tag("TY", "BOOK");
+        tag("ID", &key);
+
+        if let Some(title) = book.title.as_deref() {
+            tag("TI", title);
+        }
+
+        for author in entry.author_sort_names() {
+            tag("AU", &author);
+        }
+
+        if let Some(date) = book.available_at {
+            tag("PY", &date.year().to_string());
+            tag("DA", &date.format("%Y/%m/%d").to_string());
+        }
+
+        if let Some(publisher) = book.publisher.as_deref() {
+            tag("PB", publisher);
+        }
+
+        for isbn in book.isbns.iter().flatten() {
+            tag("SN", isbn);
+        }
+
+        tag("LA", get_language_code(book.language));
+
+        if let Some(description) = book.description.as_deref() {
+            tag("AB", description);
+        }
+
+        tag("ER", "");
+    }
+
+    out
+}
+
+/// Leader of a printed book record. The lengths and addresses are left as zeros since there's no binary record.
+static MARC_LEADER: &str = "00000nam a2200000 i 4500";
+
+/// A record in the MARC-in-JSON format.
+///
+/// Only covers what a book stores. Meant for tools which import MARC-in-JSON rather than for cataloguing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarcRecord {
+    pub leader: String,
+    pub fields: Vec<MarcField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarcField {
+    /// Tags 001 to 009. Serialized as `{ "001": "value" }`
+    Control { tag: &'static str, value: String },
+    /// Serialized as `{ "245": { "ind1": "1", "ind2": "0", "subfields": [{ "a": "value" }] } }`
+    Data {
+        tag: &'static str,
+        ind1: char,
+        ind2: char,
+        subfields: Vec<(char, String)>,
+    },
+}
+
+impl MarcField {
+    fn data(tag: &'static str, ind1: char, ind2: char, subfields: Vec<(char, String)>) -> Self {
+        Self::Data {
+            tag,
+            ind1,
+            ind2,
+            subfields,
+        }
+    }
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::Control { tag, .. } | Self::Data { tag, .. } => tag,
+        }
+    }
+
+    /// Values of the subfield with the code. Always empty for control fields.
+    pub fn subfield(&self, code: char) -> impl Iterator<Item = &str> {
+        let subfields = match self {
+            Self::Control { .. } => &[][..],
+            Self::Data { subfields, .. } => subfields.as_slice(),
+        };
+
+        subfields
+            .iter()
+            .filter(move |(c, _)| *c == code)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl Serialize for MarcField {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+
+        match self {
+            Self::Control { tag, value } => map.serialize_entry(tag, value)?,
+
+            Self::Data {
+                tag,
+                ind1,
+                ind2,
+                subfields,
+            } => {
+                let subfields = subfields
+                    .iter()
+                    .map(|(code, value)| HashMap::from([(code.to_string(), value)]))
+                    .collect::<Vec<_>>();
+
+                map.serialize_entry(
+                    tag,
+                    &serde_json::json!({
+                        "ind1": ind1.to_string(),
+                        "ind2": ind2.to_string(),
+                        "subfields": subfields,
+                    }),
+                )?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// MARC-in-JSON records using the citation keys as control numbers.
+///
+/// Maps the ISBNs (020), language (041), authors (100, 700), title (245), publisher and year (264) and
+/// description (520).
+pub fn to_marc_json(entries: &[CitationEntry]) -> Vec<MarcRecord> {
+    entries
+        .iter()
+        .zip(citation_keys(entries))
+        .map(|(entry, key)| {
+            let book = entry.book;
+
+            let mut fields = vec![MarcField::Control {
+                tag: "001",
+                value: key,
+            }];
+
+            for isbn in book.isbns.iter().flatten() {
+                fields.push(MarcField::data("020", ' ', ' ', vec![('a', isbn.clone())]));
+            }
+
+            fields.push(MarcField::data(
+                "041",
+                '0',
+                ' ',
+                vec![('a', get_language_marc_code(book.language).to_string())],
+            ));
+
+            // First author is the main entry, the rest are added entries. Both are personal names in "Family, Given" form.
+            for (i, name) in entry.author_sort_names().enumerate() {
+                let tag = if i == 0 { "100" } else { "700" };
+
+                fields.push(MarcField::data(tag, '1', ' ', vec![('a', name)]));
+            }
+
+            if let Some(title) = book.title.as_deref() {
+                fields.push(MarcField::data(
+                    "245",
+                    if entry.authors.is_empty() { '0' } else { '1' },
+                    marc_nonfiling_characters(title, book.language),
+                    vec![('a', title.to_string())],
+                ));
+            }
+
+            let mut publication = Vec::new();
+
+            if let Some(publisher) = book.publisher.as_deref() {
+                publication.push(('b', publisher.to_string()));
+            }
+
+            if let Some(date) = book.available_at {
+                publication.push(('c', date.year().to_string()));
+            }
+
+            if !publication.is_empty() {
+                fields.push(MarcField::data("264", ' ', '1', publication));
+            }
+
+            if let Some(description) = book.description.as_deref() {
+                fields.push(MarcField::data(
+                    "520",
+                    ' ',
+                    ' ',
+                    vec![('a', description.to_string())],
+                ));
+            }
+
+            MarcRecord {
+                leader: MARC_LEADER.to_string(),
+                fields,
+            }
+        })
+        .collect()
+}
+
+/// Number of leading characters to skip when sorting the title, used as the second 245 indicator.
+///
+/// "The Hobbit" -> '4'
+fn marc_nonfiling_characters(title: &str, language: u16) -> char {
+    let title = title.trim();
+
+    let skipped = sort_title(title, language)
+        .rsplit_once(", ")
+        .filter(|(rest, article)| title.ends_with(rest) && title.starts_with(article))
+        .map(|(rest, _)| title.chars().count() - rest.chars().count())
+        .unwrap_or_default();
+
+    char::from_digit(skipped.min(9) as u32, 10).unwrap_or('0')
+}
+
+/// A CSL-JSON item. Only the fields we can map to a book are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CslItem {
+    /// Some exporters use numbers.
+    #[serde(deserialize_with = "deserialize_csl_id")]
+    pub id: String,
+    #[serde(rename = "type", default)]
+    pub type_of: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<CslName>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(rename = "ISBN", default, skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(rename = "abstract", default, skip_serializing_if = "Option::is_none")]
+    pub abstract_of: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CslName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// Used for names which can't be split. eg. organizations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+}
+
+impl CslName {
+    pub fn display_name(&self) -> String {
+        if let Some(literal) = self.literal.as_deref() {
+            return literal.to_string();
+        }
+
+        let mut compiled = [self.given.as_deref(), self.family.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if let Some(suffix) = self.suffix.as_deref() {
+            compiled.push_str(", ");
+            compiled.push_str(suffix);
+        }
+
+        compiled
+    }
+}
+
+impl From<&PersonName> for CslName {
+    fn from(value: &PersonName) -> Self {
+        if value.family.is_empty() {
+            return Self {
+                literal: Some(value.given_name()),
+                ..Self::default()
+            };
+        }
+
+        Self {
+            family: Some(value.family.clone()),
+            given: Some(value.given_name()).filter(|v| !v.is_empty()),
+            suffix: value.suffix.clone(),
+            literal: None,
+        }
+    }
+}
+
+/// Either `date-parts` (`[[year, month, day]]`) or a `raw` date string.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CslDate {
+    /// Numbers or numeric strings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub date_parts: Vec<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+}
+
+impl CslDate {
+    pub fn to_naive_date(&self) -> Option<NaiveDate> {
+        if let Some(parts) = self.date_parts.first() {
+            let part = |i: usize, default: u32| -> Option<u32> {
+                match parts.get(i) {
+                    None => Some(default),
+                    Some(Value::Number(v)) => v.as_u64().and_then(|v| u32::try_from(v).ok()),
+                    Some(Value::String(v)) => v.trim().parse().ok(),
+                    Some(_) => None,
+                }
+            };
+
+            let year = i32::try_from(part(0, 0)?).ok()?;
+
+            if let Some(date) = NaiveDate::from_ymd_opt(year, part(1, 1)?, part(2, 1)?) {
+                return Some(date);
+            }
+        }
+
+        self.raw.as_deref().and_then(parse_opf_date)
+    }
+}
+
+impl From<NaiveDate> for CslDate {
+    fn from(value: NaiveDate) -> Self {
+        Self {
+            date_parts: vec![vec![
+                value.year().into(),
+                value.month().into(),
+                value.day().into(),
+            ]],
+            raw: None,
+        }
+    }
+}
+
+impl CslItem {
+    /// Creates a new, private book. `id` and `author_ids` are left for the caller to fill in.
+    pub fn to_public_book(&self, now: DateTime<Utc>) -> PublicBook {
+        PublicBook {
+            id: 0,
+            title: self.title.clone(),
+            clean_title: self.title.as_deref().map(normalize_title),
+            description: self.abstract_of.clone(),
+            rating: 0.0,
+            thumb_url: None,
+            isbns: self
+                .isbn
+                .as_deref()
+                .map(|v| {
+                    // Multiple ISBNs are sometimes separated by spaces or commas.
+                    v.split([' ', ',', ';'])
+                        .filter(|v| !v.is_empty())
+                        .map(|v| normalize_isbn(v).unwrap_or_else(|| v.to_string()))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|v| !v.is_empty()),
+            display_author_id: None,
+            publisher: self.publisher.clone(),
+            author_ids: Vec::new(),
+            is_public: false,
+            edition_count: 0,
+            available_at: self.issued.as_ref().and_then(CslDate::to_naive_date),
+            language: self
+                .language
+                .as_deref()
+                .and_then(Language::from_code)
+                .unwrap_or_default()
+                .id(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    /// Names of the authors, in order. eg. "J. R. R. Tolkien"
+    pub fn author_names(&self) -> Vec<String> {
+        self.author
+            .iter()
+            .map(CslName::display_name)
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+}
+
+/// CSL-JSON items using the citation keys as ids.
+pub fn to_csl_json(entries: &[CitationEntry]) -> Vec<CslItem> {
+    entries
+        .iter()
+        .zip(citation_keys(entries))
+        .map(|(entry, key)| CslItem {
+            id: key,
+            type_of: String::from("book"),
+            title: entry.book.title.clone(),
+            author: entry
+                .authors
+                .iter()
+                .map(|v| CslName::from(&PersonName::parse(&v.name)))
+                .collect(),
+            issued: entry.book.available_at.map(CslDate::from),
+            publisher: entry.book.publisher.clone(),
+            isbn: entry.isbn().map(str::to_string),
+            language: Some(get_language_code(entry.book.language).to_string()),
+            abstract_of: entry.book.description.clone(),
+        })
+        .collect()
+}
+
+/// Reads a CSL-JSON array. A single item is accepted as well.
+pub fn from_csl_json(value: &str) -> Result<Vec<CslItem>> {
+    let value: Value = serde_json::from_str(value)?;
+
+    if value.is_array() {
+        Ok(serde_json::from_value(value)?)
+    } else {
+        Ok(vec![serde_json::from_value(value)?])
+    }
+}
+
+fn deserialize_csl_id<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(v) => Ok(v),
+        Value::Number(v) => Ok(v.to_string()),
+        v => Err(serde::de::Error::custom(format!("invalid CSL id: {v}"))),
+    }
+}
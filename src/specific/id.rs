@@ -8,9 +8,14 @@ use std::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "backend")]
-use sqlx::{Decode, Encode, encode::IsNull, error::BoxDynError, database::{Database, HasValueRef, HasArguments}};
+use sqlx::{
+    database::{Database, HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    Decode, Encode,
+};
 
-use crate::ImageType;
+use crate::{error::Error, ImageType};
 
 #[macro_use]
 #[cfg(feature = "backend")]
@@ -240,7 +245,7 @@ where
     &'r str: Decode<'r, DB>,
 {
     fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
-        Ok(Self::from_str(<&str as Decode<DB>>::decode(value)?).unwrap())
+        Ok(Self::from_str(<&str as Decode<DB>>::decode(value)?)?)
     }
 }
 
@@ -254,6 +259,16 @@ where
     }
 }
 
+#[cfg(feature = "backend")]
+impl<DB: Database> sqlx::Type<DB> for ImageIdType
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
 impl Display for ImageIdType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.as_string().fmt(f)
@@ -261,22 +276,20 @@ impl Display for ImageIdType {
 }
 
 impl FromStr for ImageIdType {
-    type Err = crate::error::Error;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // TODO: Error handling.
-        let split = s
+        let (id, type_of) = s
             .split_once('-')
-            .and_then(|(l, r)| Some((l.parse().ok()?, ImageType::from_number(r.parse().ok()?)?)));
-
-        Ok(if let Some((id, type_of)) = split {
-            Self { id, type_of }
-        } else {
-            // TODO: Remove.
-            Self {
-                id: 0,
-                type_of: ImageType::Book,
-            }
+            .ok_or_else(|| Error::InvalidImage(s.to_string()))?;
+
+        Ok(Self {
+            id: id.parse().map_err(|_| Error::InvalidId(s.to_string()))?,
+            type_of: type_of
+                .parse()
+                .ok()
+                .and_then(ImageType::from_number)
+                .ok_or_else(|| Error::InvalidImage(s.to_string()))?,
         })
     }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::error::Error;
+
 #[cfg(feature = "backend")]
 use sqlx::{Decode, Encode, encode::IsNull, error::BoxDynError, database::{Database, HasValueRef, HasArguments}};
 
@@ -26,9 +28,14 @@ impl ImageType {
     }
 }
 
-impl From<i64> for ImageType {
-    fn from(value: i64) -> Self {
-        Self::from_number(value as u8).unwrap()
+impl TryFrom<i64> for ImageType {
+    type Error = Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .ok()
+            .and_then(Self::from_number)
+            .ok_or_else(|| Error::InvalidImage(format!("Unknown Image Type: {value}")))
     }
 }
 
@@ -49,7 +56,10 @@ where
     u8: Decode<'r, DB>,
 {
     fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
-        Ok(Self::from_number(<u8 as Decode<DB>>::decode(value)?).unwrap())
+        let value = <u8 as Decode<DB>>::decode(value)?;
+
+        Ok(Self::from_number(value)
+            .ok_or_else(|| Error::InvalidImage(format!("Unknown Image Type: {value}")))?)
     }
 }
 
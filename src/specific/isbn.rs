@@ -1,3 +1,17 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+#[cfg(feature = "backend")]
+use sqlx::{
+    database::{Database, HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    Decode, Encode,
+};
+
 // Used to help handle ids a little better "amazon:{id}", "amazon_uk:{id}", "goodreads:{id}", "isbn:{id}", "google:{id}", "uuid:{id}", "urn:uuid:{id}", "urn:isbn:{id}"
 pub fn parse_book_id(value: &str) -> IdType {
     if let Some((prefix, suffix)) = value.rsplit_once(':') {
@@ -16,6 +30,7 @@ pub fn parse_book_id(value: &str) -> IdType {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IdType {
     Isbn(String),
     Uuid(String),
@@ -78,26 +93,172 @@ impl IdType {
     }
 }
 
-// TODO: Tests
+impl fmt::Display for IdType {
+    /// Formatted so [`parse_book_id`] returns the same value.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Isbn(v) => write!(f, "isbn:{v}"),
+            Self::Uuid(v) => write!(f, "uuid:{v}"),
+            Self::UnknownKeyValue(k, v) => write!(f, "{k}:{v}"),
+            Self::UnknownValue(v) => v.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'q, DB: Database> Encode<'q, DB> for IdType
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <String as Encode<DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'r, DB: Database> Decode<'r, DB> for IdType
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> std::result::Result<Self, BoxDynError> {
+        Ok(parse_book_id(<&str as Decode<DB>>::decode(value)?))
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<DB: Database> sqlx::Type<DB> for IdType
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
+/// A valid ISBN, stored as an ISBN-13 so both forms of the same ISBN are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Isbn(String);
+
+impl Isbn {
+    pub fn parse(value: &str) -> Result<Self> {
+        validate_isbn(value).map(Self)
+    }
+
+    /// The ISBN-10 form. Only exists for ISBNs starting with 978.
+    pub fn to_isbn_10(&self) -> Option<String> {
+        let body = self.0.strip_prefix("978")?;
+
+        let sum = body[..9]
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .enumerate()
+            .map(|(i, dig)| (10 - i as u32) * dig)
+            .sum::<u32>();
+
+        let check = match (11 - sum % 11) % 11 {
+            10 => 'X',
+            v => char::from_digit(v, 10)?,
+        };
+
+        Some(format!("{}{check}", &body[..9]))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for Isbn {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Isbn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Isbn {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Isbn {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'q, DB: Database> Encode<'q, DB> for Isbn
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <String as Encode<DB>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'r, DB: Database> Decode<'r, DB> for Isbn
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> std::result::Result<Self, BoxDynError> {
+        Ok(Self::parse(<&str as Decode<DB>>::decode(value)?)?)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<DB: Database> sqlx::Type<DB> for Isbn
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
 pub fn parse_isbn_10(value: &str) -> Option<String> {
     let mut s = 0;
     let mut t = 0;
 
-    let mut parse = value.split("").filter(|v| *v != "-" && !v.is_empty());
-
     let mut compiled = String::new();
 
-    // Ensure first is a number.
-    if let Some(v) = parse.next().filter(|v| v.parse::<usize>().is_ok()) {
-        compiled.push_str(v);
-    }
+    for c in value.chars().filter(|v| *v != '-' && *v != ' ') {
+        let dig = match c {
+            '0'..='9' => c as usize - '0' as usize,
+            // Check digit of 10
+            'X' | 'x' if compiled.len() == 9 => 10,
+            _ => return None,
+        };
 
-    for dig_str in parse.take(9) {
-        let Ok(dig) = dig_str.parse::<usize>() else {
+        if compiled.len() == 10 {
             return None;
-        };
+        }
 
-        compiled.push_str(dig_str);
+        compiled.push(c.to_ascii_uppercase());
 
         t += dig;
         s += t;
@@ -106,23 +267,15 @@ pub fn parse_isbn_10(value: &str) -> Option<String> {
     Some(compiled).filter(|v| s != 0 && v.len() == 10 && (s % 11) == 0)
 }
 
-// TODO: Tests
 pub fn parse_isbn_13(value: &str) -> Option<String> {
     let mut s = 0;
 
     let mut compiled = String::new();
 
-    for (i, dig_str) in value
-        .split("")
-        .filter(|v| *v != "-" && !v.is_empty())
-        .take(13)
-        .enumerate()
-    {
-        let Ok(dig) = dig_str.parse::<usize>() else {
-            return None;
-        };
+    for (i, c) in value.chars().filter(|v| *v != '-' && *v != ' ').enumerate() {
+        let dig = c.to_digit(10).filter(|_| i < 13)?;
 
-        compiled.push_str(dig_str);
+        compiled.push(c);
 
         let weight = if i % 2 == 0 { 1 } else { 3 };
         s += dig * weight;
@@ -132,27 +285,31 @@ pub fn parse_isbn_13(value: &str) -> Option<String> {
 }
 
 pub fn isbn_10_to_13(value: &str) -> Option<String> {
-    let mut s = 0;
-
-    let current = value.split("").filter(|v| !v.is_empty()).take(9);
-    let iter = "978".split("").filter(|v| !v.is_empty()).chain(current);
+    let value = parse_isbn_10(value)?;
 
-    let mut compiled = String::new();
-
-    for (i, dig_str) in iter.enumerate() {
-        println!("[{}]: {}", i, dig_str);
+    let mut s = 0;
 
-        let Ok(dig) = dig_str.parse::<usize>() else {
-            return None;
-        };
+    let mut compiled = String::from("978");
+    compiled.push_str(&value[..9]);
 
-        compiled.push_str(dig_str);
+    for (i, c) in compiled.chars().enumerate() {
+        let dig = c.to_digit(10)?;
 
         let weight = if i % 2 == 0 { 1 } else { 3 };
         s += dig * weight;
     }
 
-    compiled.push_str(&(10 - (s % 10)).to_string());
+    compiled.push_str(&((10 - (s % 10)) % 10).to_string());
 
     Some(compiled)
 }
+
+/// Returns the ISBN as an ISBN-13 so both forms of the same ISBN can be compared.
+pub fn normalize_isbn(value: &str) -> Option<String> {
+    parse_isbn_13(value).or_else(|| isbn_10_to_13(value))
+}
+
+/// Like [`normalize_isbn`] but returns an error if it isn't a valid ISBN.
+pub fn validate_isbn(value: &str) -> Result<String> {
+    normalize_isbn(value).ok_or_else(|| Error::InvalidIsbn(value.to_string()))
+}
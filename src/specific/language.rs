@@ -1,4 +1,48 @@
-pub static LANGUAGES: [&str; 1] = ["english"];
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "backend")]
+use sqlx::{
+    database::{Database, HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    Decode, Encode,
+};
+
+pub static LANGUAGES: [&str; 7] = [
+    "english",
+    "french",
+    "german",
+    "spanish",
+    "italian",
+    "dutch",
+    "portuguese",
+];
+
+/// Leading articles for each of the [`LANGUAGES`], in the same order.
+///
+/// Elided articles (eg. "l'") end with an apostrophe and don't need a following space.
+static LANGUAGE_ARTICLES: [&[&str]; 7] = [
+    &["the", "a", "an"],
+    &["le", "la", "les", "l'", "un", "une", "des"],
+    &["der", "die", "das", "den", "dem", "des", "ein", "eine"],
+    &["el", "la", "los", "las", "un", "una", "unos", "unas"],
+    &["il", "lo", "la", "i", "gli", "le", "l'", "un", "uno", "una"],
+    &["de", "het", "een"],
+    &["o", "a", "os", "as", "um", "uma"],
+];
+
+/// ISO 639-1 and 639-2 (bibliographic and terminology) codes for each of the [`LANGUAGES`], in the same order.
+static LANGUAGE_CODES: [&[&str]; 7] = [
+    &["en", "eng"],
+    &["fr", "fre", "fra"],
+    &["de", "ger", "deu"],
+    &["es", "spa"],
+    &["it", "ita"],
+    &["nl", "dut", "nld"],
+    &["pt", "por"],
+];
 
 pub fn get_language_id(value: &str) -> u16 {
     let value = value.to_lowercase();
@@ -14,3 +58,122 @@ pub fn get_language_name(value: u16) -> &'static str {
         .map(|v| *v)
         .unwrap_or(LANGUAGES[0])
 }
+
+/// Resolves a language code (eg. "en", "fre", "pt-BR") or a name from [`LANGUAGES`].
+pub fn find_language_id(value: &str) -> Option<u16> {
+    let value = value.trim().to_lowercase();
+
+    // Drop the region. eg. "en-US", "pt_BR"
+    let code = value.split(['-', '_']).next().unwrap_or_default();
+
+    LANGUAGE_CODES
+        .iter()
+        .position(|codes| codes.contains(&code))
+        .or_else(|| LANGUAGES.iter().position(|v| *v == value))
+        .map(|v| v as u16)
+}
+
+/// ISO 639-1 code of the language. eg. "en"
+pub fn get_language_code(value: u16) -> &'static str {
+    LANGUAGE_CODES
+        .get(value as usize)
+        .copied()
+        .unwrap_or(LANGUAGE_CODES[0])[0]
+}
+
+/// The MARC 21 (ISO 639-2/B) code. eg. "fre" instead of "fra"
+pub fn get_language_marc_code(value: u16) -> &'static str {
+    LANGUAGE_CODES
+        .get(value as usize)
+        .copied()
+        .unwrap_or(LANGUAGE_CODES[0])[1]
+}
+
+pub fn get_language_articles(value: u16) -> &'static [&'static str] {
+    LANGUAGE_ARTICLES
+        .get(value as usize)
+        .copied()
+        .unwrap_or(LANGUAGE_ARTICLES[0])
+}
+
+/// Index into [`LANGUAGES`]. Unknown ids are treated as the first language.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Language(u16);
+
+impl Language {
+    pub fn from_name(value: &str) -> Self {
+        Self(get_language_id(value))
+    }
+
+    /// See [`find_language_id`].
+    pub fn from_code(value: &str) -> Option<Self> {
+        find_language_id(value).map(Self)
+    }
+
+    pub fn id(self) -> u16 {
+        self.0
+    }
+
+    pub fn name(self) -> &'static str {
+        get_language_name(self.0)
+    }
+
+    pub fn code(self) -> &'static str {
+        get_language_code(self.0)
+    }
+
+    pub fn articles(self) -> &'static [&'static str] {
+        get_language_articles(self.0)
+    }
+}
+
+impl From<u16> for Language {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Language> for u16 {
+    fn from(value: Language) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'q, DB: Database> Encode<'q, DB> for Language
+where
+    u16: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <u16 as Encode<DB>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'r, DB: Database> Decode<'r, DB> for Language
+where
+    u16: Decode<'r, DB>,
+{
+    fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        Ok(Self(<u16 as Decode<DB>>::decode(value)?))
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<DB: Database> sqlx::Type<DB> for Language
+where
+    u16: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <u16 as sqlx::Type<DB>>::type_info()
+    }
+}
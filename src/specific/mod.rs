@@ -1,16 +1,26 @@
 use serde::{Deserialize, Serialize};
 
+mod book_match;
+mod citation;
 mod id;
 mod image;
 mod isbn;
 mod language;
+mod name;
+mod opf;
 mod source;
+mod title;
 
+pub use book_match::*;
+pub use citation::*;
 pub use id::*;
 pub use image::*;
 pub use isbn::*;
 pub use language::*;
+pub use name::*;
+pub use opf::*;
 pub use source::*;
+pub use title::*;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
 pub enum Either<A, B> {
@@ -0,0 +1,353 @@
+use std::fmt;
+
+use crate::util::{normalize_for_comparison, similarity, upper_case_first_char};
+
+/// Lowercase words which belong to the family name. eg. "Ludwig van Beethoven"
+static FAMILY_PARTICLES: [&str; 16] = [
+    "van", "von", "der", "den", "de", "del", "della", "di", "da", "du", "des", "la", "le", "ter",
+    "bin", "ibn",
+];
+
+static SUFFIXES: [&str; 8] = ["jr", "sr", "phd", "md", "esq", "obe", "mbe", "kbe"];
+
+/// Generational suffixes which can also be read as a name or initials. eg. "Smith, Ii"
+///
+/// Only a suffix in the last place and when there's a given name left.
+static NUMERAL_SUFFIXES: [&str; 3] = ["ii", "iii", "iv"];
+
+/// Score from [`PersonName::similarity`] at which two names are treated as the same person.
+pub const SAME_PERSON_THRESHOLD: f64 = 0.85;
+
+/// A person's name split into its parts.
+///
+/// ```ignore
+/// let name = PersonName::parse("Tolkien, J.R.R.");
+///
+/// assert_eq!(name.display_name(), "J. R. R. Tolkien");
+/// assert_eq!(name.sort_name(), "Tolkien, J. R. R.");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PersonName {
+    /// Given and middle names. Initials are stored as "J."
+    pub given: Vec<String>,
+    /// Family name including particles. eg. "van Beethoven"
+    pub family: String,
+    /// eg. "Jr.", "III" or "Jr. PhD"
+    pub suffix: Option<String>,
+}
+
+impl PersonName {
+    /// Parses both "Given Family" and "Family, Given" forms.
+    pub fn parse(value: &str) -> Self {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let mut parts = value
+            .split(',')
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut suffix = None;
+
+        // "Martin Luther King, Jr." or "Tolkien, J. R. R., Jr."
+        if parts.len() > 1
+            && parts
+                .last()
+                .map(|v| is_suffix(v, parts.len() > 2))
+                .unwrap_or_default()
+        {
+            suffix = parts.pop().map(normalize_suffix);
+        }
+
+        let (given, family) = if parts.len() > 1 {
+            // Family, Given
+            let mut given = split_tokens(&parts[1..].join(" "));
+
+            if suffix.is_none()
+                && given
+                    .last()
+                    .map(|v| is_suffix(v, given.len() > 1))
+                    .unwrap_or_default()
+            {
+                suffix = given.pop().map(|v| normalize_suffix(&v));
+            }
+
+            (given, split_tokens(parts[0]))
+        } else {
+            // Given Family
+            let mut tokens = split_tokens(parts.first().copied().unwrap_or_default());
+
+            // Popped from the end so they're in reverse. eg. "John Smith Jr. PhD"
+            let mut suffixes = Vec::new();
+
+            while tokens.len() > 1
+                && tokens
+                    .last()
+                    .map(|v| is_suffix(v, suffixes.is_empty() && tokens.len() > 2))
+                    .unwrap_or_default()
+            {
+                suffixes.extend(tokens.pop().map(|v| normalize_suffix(&v)));
+            }
+
+            if !suffixes.is_empty() {
+                suffixes.reverse();
+                // After the comma. eg. "John Smith Jr., PhD"
+                suffixes.extend(suffix.take());
+                suffix = Some(suffixes.join(" "));
+            }
+
+            // Family name is the last word along with any particles before it.
+            let mut family_start = tokens.len().saturating_sub(1);
+
+            while family_start > 1 && is_particle(&tokens[family_start - 1]) {
+                family_start -= 1;
+            }
+
+            let family = tokens.split_off(family_start);
+
+            (tokens, family)
+        };
+
+        let is_single_case =
+            !value.chars().any(|c| c.is_lowercase()) || !value.chars().any(|c| c.is_uppercase());
+
+        let fix_case = |value: String| {
+            if is_single_case && !is_initial(&value) {
+                if is_particle(&value) {
+                    value.to_lowercase()
+                } else {
+                    capitalize(&value)
+                }
+            } else {
+                value
+            }
+        };
+
+        Self {
+            given: given.into_iter().map(fix_case).collect(),
+            family: family
+                .into_iter()
+                .map(fix_case)
+                .collect::<Vec<_>>()
+                .join(" "),
+            suffix,
+        }
+    }
+
+    pub fn given_name(&self) -> String {
+        self.given.join(" ")
+    }
+
+    /// "J. R. R. Tolkien", "Martin Luther King Jr."
+    pub fn display_name(&self) -> String {
+        let mut compiled = self.given_name();
+
+        if !self.family.is_empty() {
+            if !compiled.is_empty() {
+                compiled.push(' ');
+            }
+
+            compiled.push_str(&self.family);
+        }
+
+        if let Some(suffix) = self.suffix.as_deref() {
+            compiled.push(' ');
+            compiled.push_str(suffix);
+        }
+
+        compiled
+    }
+
+    /// "Tolkien, J. R. R.", "King, Martin Luther, Jr."
+    pub fn sort_name(&self) -> String {
+        let mut compiled = self.family.clone();
+
+        if !self.given.is_empty() {
+            if !compiled.is_empty() {
+                compiled.push_str(", ");
+            }
+
+            compiled.push_str(&self.given_name());
+        }
+
+        if let Some(suffix) = self.suffix.as_deref() {
+            compiled.push_str(", ");
+            compiled.push_str(suffix);
+        }
+
+        compiled
+    }
+
+    /// Scores how likely it is that both names refer to the same person, from `0.0` to `1.0`.
+    ///
+    /// Initials match any name starting with the same letter and missing given names are only lightly penalized.
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let family_score = similarity(
+            &normalize_for_comparison(&self.family),
+            &normalize_for_comparison(&other.family),
+        );
+
+        // Possibly parsed the wrong way around. eg. "Haruki Murakami" vs "Murakami Haruki"
+        if family_score < 0.8 {
+            let mut left = normalized_tokens(&self.display_name());
+            let mut right = normalized_tokens(&other.display_name());
+
+            left.sort_unstable();
+            right.sort_unstable();
+
+            return if left == right {
+                0.9
+            } else {
+                family_score * 0.6
+            };
+        }
+
+        let given_score = given_similarity(&self.given, &other.given);
+
+        family_score * 0.6 + given_score * 0.4
+    }
+
+    pub fn is_likely_same_person(&self, other: &Self) -> bool {
+        self.similarity(other) >= SAME_PERSON_THRESHOLD
+    }
+}
+
+impl fmt::Display for PersonName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_name().fmt(f)
+    }
+}
+
+impl From<&str> for PersonName {
+    fn from(value: &str) -> Self {
+        Self::parse(value)
+    }
+}
+
+/// Scores two unparsed names. See [`PersonName::similarity`].
+pub fn name_similarity(left: &str, right: &str) -> f64 {
+    PersonName::parse(left).similarity(&PersonName::parse(right))
+}
+
+fn given_similarity(left: &[String], right: &[String]) -> f64 {
+    if left.is_empty() || right.is_empty() {
+        // "Tolkien" vs "J. R. R. Tolkien". Probably the same, but we can't tell.
+        return if left.is_empty() && right.is_empty() {
+            1.0
+        } else {
+            0.6
+        };
+    }
+
+    let left = left
+        .iter()
+        .map(|v| normalize_for_comparison(v))
+        .collect::<Vec<_>>();
+    let right = right
+        .iter()
+        .map(|v| normalize_for_comparison(v))
+        .collect::<Vec<_>>();
+
+    let mut total = 0.0;
+
+    for (l, r) in left.iter().zip(right.iter()) {
+        total += if l == r {
+            1.0
+        } else if (l.len() == 1 || r.len() == 1) && l.chars().next() == r.chars().next() {
+            0.9
+        } else {
+            similarity(l, r).powi(2)
+        };
+    }
+
+    let compared = left.len().min(right.len()) as f64;
+    let score = total / compared;
+
+    // A missing middle name shouldn't count for much. "John Smith" vs "John A. Smith"
+    if left.len() != right.len() {
+        score * 0.95
+    } else {
+        score
+    }
+}
+
+/// Splits on whitespace and separates initials. "J.R.R." -> ["J.", "R.", "R."]
+fn split_tokens(value: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+
+    for word in value.split_whitespace() {
+        let letters = word
+            .split('.')
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+
+        if word.contains('.') && letters.iter().all(|v| v.chars().count() == 1) {
+            for letter in letters {
+                tokens.push(format!("{}.", letter.to_uppercase()));
+            }
+        } else if word.chars().count() == 1 && word.chars().all(char::is_alphabetic) {
+            tokens.push(format!("{}.", word.to_uppercase()));
+        } else {
+            tokens.push(word.to_string());
+        }
+    }
+
+    tokens
+}
+
+fn normalized_tokens(value: &str) -> Vec<String> {
+    normalize_for_comparison(value)
+        .split(' ')
+        .map(|v| v.to_string())
+        .collect()
+}
+
+fn is_initial(value: &str) -> bool {
+    value.ends_with('.') && value.chars().count() == 2
+}
+
+fn is_particle(value: &str) -> bool {
+    FAMILY_PARTICLES.contains(&value.to_lowercase().as_str())
+}
+
+/// `allow_numeral` if it's in the last place after a given name. See [`NUMERAL_SUFFIXES`].
+fn is_suffix(value: &str, allow_numeral: bool) -> bool {
+    let value = value.replace('.', "").to_lowercase();
+
+    SUFFIXES.contains(&value.as_str())
+        || (allow_numeral && NUMERAL_SUFFIXES.contains(&value.as_str()))
+}
+
+fn normalize_suffix(value: &str) -> String {
+    let stripped = value.replace('.', "");
+
+    match stripped.to_lowercase().as_str() {
+        "jr" => String::from("Jr."),
+        "sr" => String::from("Sr."),
+        "phd" => String::from("PhD"),
+        "md" => String::from("MD"),
+        "esq" => String::from("Esq."),
+        _ => stripped.to_uppercase(),
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    // Keep hyphenated and apostrophe names readable. "jean-paul" -> "Jean-Paul", "o'brien" -> "O'Brien"
+    let mut compiled = String::with_capacity(value.len());
+
+    for (i, part) in value.to_lowercase().split('-').enumerate() {
+        if i != 0 {
+            compiled.push('-');
+        }
+
+        if let Some((first, rest)) = part.split_once('\'') {
+            compiled.push_str(&upper_case_first_char(first.to_string()));
+            compiled.push('\'');
+            compiled.push_str(&upper_case_first_char(rest.to_string()));
+        } else {
+            compiled.push_str(&upper_case_first_char(part.to_string()));
+        }
+    }
+
+    compiled
+}
@@ -0,0 +1,356 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use roxmltree::{Document, Node};
+
+use crate::{
+    api::librarian::PublicBook,
+    error::{Error, Result},
+    normalize_title, parse_book_id,
+    util::strip_html,
+    IdType, Language,
+};
+
+static DC_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+static OPF_NAMESPACE: &str = "http://www.idpf.org/2007/opf";
+
+/// Metadata read from the `<metadata>` of an EPUB package document (OPF).
+///
+/// Supports both EPUB 2 attributes (`opf:role`, `opf:file-as`, `opf:scheme`) and EPUB 3 `refines` meta elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpfMetadata {
+    pub title: Option<String>,
+    pub clean_title: Option<String>,
+    pub description: Option<String>,
+    pub publisher: Option<String>,
+
+    /// ISBN 13s first, then ISBN 10s. No duplicates.
+    pub isbns: Vec<String>,
+    /// Every `dc:identifier`, including the ISBNs.
+    pub identifiers: Vec<IdType>,
+
+    pub contributors: Vec<OpfContributor>,
+
+    pub available_at: Option<NaiveDate>,
+    /// `None` if the language isn't in our table.
+    pub language: Option<Language>,
+
+    pub series: Option<OpfSeries>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpfContributor {
+    pub name: String,
+    /// Sort form of the name. eg. "Tolkien, J. R. R."
+    pub file_as: Option<String>,
+    pub role: ContributorRole,
+}
+
+/// MARC relator codes we care about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContributorRole {
+    Author,
+    Editor,
+    Illustrator,
+    Translator,
+    Narrator,
+    Other(String),
+}
+
+impl ContributorRole {
+    pub fn from_code(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "aut" => Self::Author,
+            "edt" => Self::Editor,
+            "ill" => Self::Illustrator,
+            "trl" => Self::Translator,
+            "nrt" => Self::Narrator,
+            v => Self::Other(v.to_string()),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Author => "aut",
+            Self::Editor => "edt",
+            Self::Illustrator => "ill",
+            Self::Translator => "trl",
+            Self::Narrator => "nrt",
+            Self::Other(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpfSeries {
+    pub name: String,
+    pub index: Option<f64>,
+}
+
+impl OpfMetadata {
+    /// Parses the package document. Fails if it isn't XML or doesn't contain a `<metadata>` element.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let document = Document::parse(xml)?;
+
+        let metadata = document
+            .descendants()
+            .find(|v| v.has_tag_name((OPF_NAMESPACE, "metadata")) || v.has_tag_name("metadata"))
+            .ok_or_else(|| Error::InvalidOpf(String::from("missing <metadata>")))?;
+
+        let refines = Refines::new(metadata);
+
+        let mut title = None;
+
+        for node in dc_elements(metadata, "title") {
+            let Some(value) = node_text(node) else {
+                continue;
+            };
+
+            match refines.get(node, "title-type") {
+                Some("main") => {
+                    title = Some(value);
+                    break;
+                }
+
+                // Subtitles, collection titles, etc.
+                Some(_) => (),
+
+                None if title.is_none() => title = Some(value),
+                None => (),
+            }
+        }
+
+        let mut identifiers = Vec::new();
+        let mut isbns_13 = Vec::new();
+        let mut isbns_10 = Vec::new();
+
+        for node in dc_elements(metadata, "identifier") {
+            let Some(value) = node_text(node) else {
+                continue;
+            };
+
+            let id = match node.attribute((OPF_NAMESPACE, "scheme")) {
+                Some(scheme) if !value.contains(':') => parse_book_id(&format!("{scheme}:{value}")),
+                _ => parse_book_id(&value),
+            };
+
+            if let Some(isbn) = id.as_isbn_13() {
+                isbns_13.push(isbn);
+            } else if let Some(isbn) = id.as_isbn_10() {
+                isbns_10.push(isbn);
+            }
+
+            identifiers.push(id);
+        }
+
+        let mut isbns = Vec::new();
+
+        for isbn in isbns_13.into_iter().chain(isbns_10) {
+            if !isbns.contains(&isbn) {
+                isbns.push(isbn);
+            }
+        }
+
+        let contributors = dc_elements(metadata, "creator")
+            .map(|v| (v, ContributorRole::Author))
+            .chain(
+                dc_elements(metadata, "contributor")
+                    .map(|v| (v, ContributorRole::Other(String::from("ctb")))),
+            )
+            .filter_map(|(node, default_role)| {
+                let name = node_text(node)?;
+
+                let role = node
+                    .attribute((OPF_NAMESPACE, "role"))
+                    .or_else(|| refines.get(node, "role"))
+                    .map(ContributorRole::from_code)
+                    .unwrap_or(default_role);
+
+                let file_as = node
+                    .attribute((OPF_NAMESPACE, "file-as"))
+                    .or_else(|| refines.get(node, "file-as"))
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty());
+
+                Some(OpfContributor {
+                    name,
+                    file_as,
+                    role,
+                })
+            })
+            .collect();
+
+        // Prefer the publication date if the dates are labelled. (EPUB 2)
+        let available_at = dc_elements(metadata, "date")
+            .find(|v| {
+                v.attribute((OPF_NAMESPACE, "event"))
+                    .is_some_and(|v| v.eq_ignore_ascii_case("publication"))
+            })
+            .or_else(|| dc_elements(metadata, "date").next())
+            .and_then(node_text)
+            .and_then(|v| parse_opf_date(&v));
+
+        let language = dc_elements(metadata, "language")
+            .filter_map(node_text)
+            .find_map(|v| Language::from_code(&v));
+
+        let description = dc_elements(metadata, "description")
+            .find_map(node_text)
+            .map(|v| strip_html(&v))
+            .filter(|v| !v.is_empty());
+
+        let publisher = dc_elements(metadata, "publisher").find_map(node_text);
+
+        let series = parse_series(metadata, &refines);
+
+        Ok(Self {
+            clean_title: title.as_deref().map(normalize_title),
+            title,
+            description,
+            publisher,
+            isbns,
+            identifiers,
+            contributors,
+            available_at,
+            language,
+            series,
+        })
+    }
+
+    /// Contributors with the [`ContributorRole::Author`] role.
+    pub fn authors(&self) -> impl Iterator<Item = &OpfContributor> {
+        self.contributors
+            .iter()
+            .filter(|v| v.role == ContributorRole::Author)
+    }
+
+    /// A new, private book. `id` and `author_ids` are left for the caller to fill in after inserting the contributors.
+    pub fn to_public_book(&self, now: DateTime<Utc>) -> PublicBook {
+        PublicBook {
+            id: 0,
+            title: self.title.clone(),
+            clean_title: self.clean_title.clone(),
+            description: self.description.clone(),
+            rating: 0.0,
+            thumb_url: None,
+            isbns: Some(self.isbns.clone()).filter(|v| !v.is_empty()),
+            display_author_id: None,
+            publisher: self.publisher.clone(),
+            author_ids: Vec::new(),
+            is_public: false,
+            edition_count: 0,
+            available_at: self.available_at,
+            language: self.language.unwrap_or_default().id(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+}
+
+/// Accepts "YYYY", "YYYY-MM", "YYYY-MM-DD" and full timestamps.
+///
+/// Returns `None` for Calibre's "0101-01-01T00:00:00+00:00" placeholder which it writes when no date is set.
+pub fn parse_opf_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+
+    let date = if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        date.date_naive()
+    } else {
+        let mut parts = value.get(..10).unwrap_or(value).splitn(3, '-');
+
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next().map(|v| v.parse().ok()).unwrap_or(Some(1))?;
+        let day = parts.next().map(|v| v.parse().ok()).unwrap_or(Some(1))?;
+
+        NaiveDate::from_ymd_opt(year, month, day)?
+    };
+
+    Some(date).filter(|v| v.year() > 101)
+}
+
+/// Calibre stores the series in `<meta name="calibre:series">`, EPUB 3 uses `belongs-to-collection`.
+fn parse_series(metadata: Node, refines: &Refines) -> Option<OpfSeries> {
+    let calibre = |name: &str| {
+        meta_elements(metadata)
+            .find(|v| v.attribute("name") == Some(name))
+            .and_then(|v| v.attribute("content"))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    if let Some(name) = calibre("calibre:series") {
+        return Some(OpfSeries {
+            name,
+            index: calibre("calibre:series_index").and_then(|v| v.parse().ok()),
+        });
+    }
+
+    let collection = meta_elements(metadata).find(|v| {
+        v.attribute("property") == Some("belongs-to-collection")
+            && matches!(refines.get(*v, "collection-type"), None | Some("series"))
+    })?;
+
+    Some(OpfSeries {
+        name: node_text(collection)?,
+        index: refines
+            .get(collection, "group-position")
+            .and_then(|v| v.trim().parse().ok()),
+    })
+}
+
+/// EPUB 3 `<meta refines="#id" property="..">` values.
+struct Refines<'a, 'input> {
+    metas: Vec<Node<'a, 'input>>,
+}
+
+impl<'a, 'input> Refines<'a, 'input> {
+    fn new(metadata: Node<'a, 'input>) -> Self {
+        Self {
+            metas: meta_elements(metadata)
+                .filter(|v| v.attribute("refines").is_some())
+                .collect(),
+        }
+    }
+
+    fn get(&self, node: Node, property: &str) -> Option<&'a str> {
+        let id = node.attribute("id")?;
+
+        self.metas
+            .iter()
+            .find(|v| {
+                v.attribute("refines").map(|v| v.trim_start_matches('#')) == Some(id)
+                    && v.attribute("property") == Some(property)
+            })
+            .and_then(|v| v.text())
+            .map(str::trim)
+    }
+}
+
+fn dc_elements<'a, 'input: 'a>(
+    metadata: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    metadata
+        .descendants()
+        .filter(move |v| v.has_tag_name((DC_NAMESPACE, name)))
+}
+
+fn meta_elements<'a, 'input: 'a>(
+    metadata: Node<'a, 'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    metadata
+        .descendants()
+        .filter(|v| v.is_element() && v.tag_name().name() == "meta")
+}
+
+/// Trimmed text of the element and its children. `None` if empty.
+fn node_text(node: Node) -> Option<String> {
+    let text = node
+        .descendants()
+        .filter(|v| v.is_text())
+        .filter_map(|v| v.text())
+        .collect::<String>();
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Some(text).filter(|v| !v.is_empty())
+}
@@ -71,27 +71,11 @@ impl TryFrom<&str> for Source {
     }
 }
 
-// impl TryFrom<String> for Source {
-//     type Error = Error;
-
-//     fn try_from(value: String) -> Result<Self, Self::Error> {
-//         let (source, value) = value.split_once(':').ok_or(Error::SourceSplit)?;
-
-//         Ok(Self {
-//             agent: Agent(Cow::Owned(source.to_owned())),
-//             value: value.to_owned(),
-//         })
-//     }
-// }
-// TODO: Have to use for sqlx.
-impl From<String> for Source {
-    fn from(value: String) -> Self {
-        let (source, value) = value.split_once(':').ok_or(Error::SourceSplit).unwrap();
-
-        Self {
-            agent: Agent(Cow::Owned(source.to_owned())),
-            value: value.to_owned(),
-        }
+impl TryFrom<String> for Source {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
     }
 }
 
@@ -119,6 +103,36 @@ impl Serialize for Source {
 #[cfg(feature = "backend")]
 use sqlx::{Decode, Encode, encode::IsNull, error::BoxDynError, database::{Database, HasValueRef, HasArguments}};
 
+#[cfg(feature = "backend")]
+impl<'q, DB: Database> Encode<'q, DB> for Agent
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <String as Encode<DB>>::encode(self.0.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<'r, DB: Database> Decode<'r, DB> for Agent
+where
+    String: Decode<'r, DB>,
+{
+    fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        Ok(Self::new_owned(<String as Decode<DB>>::decode(value)?))
+    }
+}
+
+#[cfg(feature = "backend")]
+impl<DB: Database> sqlx::Type<DB> for Agent
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
 #[cfg(feature = "backend")]
 impl<'q, DB: Database> Encode<'q, DB> for Source
 where
@@ -135,7 +149,7 @@ where
     String: Decode<'r, DB>,
 {
     fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
-        Ok(Self::try_from(<String as Decode<DB>>::decode(value)?).unwrap())
+        Ok(Self::try_from(<String as Decode<DB>>::decode(value)?)?)
     }
 }
 
@@ -0,0 +1,220 @@
+use crate::{get_language_articles, util::parse_roman_numeral};
+
+/// Words which mark a bracketed group as series, edition or format information.
+static JUNK_GROUP_WORDS: [&str; 19] = [
+    "book",
+    "books",
+    "vol",
+    "volume",
+    "edition",
+    "ed",
+    "series",
+    "unabridged",
+    "abridged",
+    "novel",
+    "part",
+    "kindle",
+    "ebook",
+    "paperback",
+    "hardcover",
+    "audiobook",
+    "illustrated",
+    "reprint",
+    "omnibus",
+];
+
+/// Words which, at the end of a title, mark an edition note. eg. "Dune, 40th Anniversary Edition"
+static EDITION_WORDS: [&str; 3] = ["edition", "ed", "printing"];
+
+/// Creates the `clean_title` of a book.
+///
+/// Removes series markers (`(Book 3)`, `#2`), subtitles, edition notes and bracketed junk.
+///
+/// `"Leviathan Wakes: The Expanse, Book 1 (Kindle Edition)"` -> `"Leviathan Wakes"`
+pub fn normalize_title(value: &str) -> String {
+    let mut compiled = remove_bracket_groups(value);
+
+    // Subtitles
+    for sep in [": ", " - ", " – ", " — ", "; "] {
+        if let Some((title, _)) = compiled.split_once(sep) {
+            if !title.trim().is_empty() {
+                compiled = title.to_string();
+            }
+        }
+    }
+
+    // A colon without a following space. eg. "Title:Subtitle"
+    if let Some((title, _)) = compiled.split_once(':') {
+        if !title.trim().is_empty() {
+            compiled = title.to_string();
+        }
+    }
+
+    let compiled = remove_series_markers(&compiled);
+    let compiled = remove_series_group(&compiled);
+    let compiled = remove_edition_note(&compiled);
+
+    let compiled = compiled.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let trimmed = compiled.trim_end_matches(|c: char| {
+        c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '–' | '—' | '/')
+    });
+
+    if trimmed.is_empty() {
+        // Don't lose the title entirely.
+        value.split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Creates a title used for sorting by moving the leading article to the end.
+///
+/// `language` is the id from [`get_language_id`](crate::get_language_id).
+///
+/// `"The Hobbit"` -> `"Hobbit, The"`, `"L'Étranger"` -> `"Étranger, L'"`
+pub fn sort_title(value: &str, language: u16) -> String {
+    let value = value
+        .trim()
+        .trim_start_matches(['"', '“', '‘', '«', '¡', '¿', '.', '…'])
+        .trim_end_matches(['"', '”', '’', '»'])
+        .trim();
+
+    for &article in get_language_articles(language) {
+        let Some(prefix) = value.get(..article.len()) else {
+            continue;
+        };
+
+        if !prefix.eq_ignore_ascii_case(article) && prefix.to_lowercase() != article {
+            continue;
+        }
+
+        let rest = &value[article.len()..];
+
+        let rest = if article.ends_with('\'') {
+            rest.trim_start()
+        } else if let Some(rest) = rest.strip_prefix(char::is_whitespace) {
+            rest.trim_start()
+        } else {
+            // Part of a longer word. eg. "Anthem"
+            continue;
+        };
+
+        if rest.is_empty() {
+            break;
+        }
+
+        return format!("{rest}, {prefix}");
+    }
+
+    value.to_string()
+}
+
+/// Removes `[...]` and `{...}` groups, and `(...)` groups containing series or edition information.
+fn remove_bracket_groups(value: &str) -> String {
+    let mut compiled = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find(['(', '[', '{']) {
+        let close = match rest.as_bytes()[start] {
+            b'(' => ')',
+            b'[' => ']',
+            _ => '}',
+        };
+
+        let Some(end) = rest[start..].find(close).map(|i| start + i) else {
+            break;
+        };
+
+        let inner = &rest[start + 1..end];
+
+        compiled.push_str(&rest[..start]);
+
+        if close == ')' && !is_junk_group(inner) {
+            compiled.push_str(&rest[start..=end]);
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    compiled.push_str(rest);
+
+    compiled
+}
+
+fn is_junk_group(value: &str) -> bool {
+    value.contains('#')
+        || value.chars().any(|c| c.is_ascii_digit())
+        || value
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| JUNK_GROUP_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Removes `#2` and `#2.5`.
+fn remove_series_markers(value: &str) -> String {
+    let words = value.split_whitespace().collect::<Vec<_>>();
+
+    let mut compiled = Vec::with_capacity(words.len());
+
+    for word in words {
+        let is_marker = word
+            .strip_prefix('#')
+            .map(|v| {
+                let v = v.trim_end_matches(|c: char| c.is_ascii_punctuation());
+                !v.is_empty() && v.chars().all(|c| c.is_ascii_digit() || c == '.')
+            })
+            .unwrap_or_default();
+
+        if !is_marker {
+            compiled.push(word);
+        }
+    }
+
+    compiled.join(" ")
+}
+
+/// Removes a trailing comma separated `, Book 3` / `, Vol. 2` / `, Volume II`.
+///
+/// Without the comma it's usually part of the title. eg. "The Godfather Part II"
+fn remove_series_group(value: &str) -> String {
+    if let Some((title, group)) = value.rsplit_once(',') {
+        let words = group.split_whitespace().collect::<Vec<_>>();
+
+        if let [marker, number] = words[..] {
+            let marker = marker.trim_end_matches('.').to_lowercase();
+
+            if matches!(marker.as_str(), "book" | "vol" | "volume" | "part")
+                && is_number_like(number)
+                && !title.trim().is_empty()
+            {
+                return title.to_string();
+            }
+        }
+    }
+
+    value.to_string()
+}
+
+/// Removes a trailing edition note after a comma. eg. "Dune, Deluxe Edition"
+fn remove_edition_note(value: &str) -> String {
+    if let Some((title, note)) = value.rsplit_once(',') {
+        let is_edition = note
+            .split_whitespace()
+            .last()
+            .map(|v| EDITION_WORDS.contains(&v.trim_end_matches('.').to_lowercase().as_str()))
+            .unwrap_or_default();
+
+        if is_edition && !title.trim().is_empty() {
+            return title.to_string();
+        }
+    }
+
+    value.to_string()
+}
+
+fn is_number_like(value: &str) -> bool {
+    let value = value.trim_end_matches(|c: char| c.is_ascii_punctuation());
+
+    !value.is_empty()
+        && (value.chars().all(|c| c.is_ascii_digit()) || parse_roman_numeral(value).is_some())
+}
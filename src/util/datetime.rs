@@ -0,0 +1,248 @@
+//! Serde adapters for [`DateTime<Utc>`], usable with `#[serde(with = "...")]`.
+//!
+//! Each format has an `option` module for `Option<DateTime<Utc>>` and an `option_option`
+//! module for `Option<Option<DateTime<Utc>>>`. The latter should be paired with
+//! `#[serde(default, skip_serializing_if = "Option::is_none")]` so a missing field stays `None`
+//! while an explicit `null` becomes `Some(None)`.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Item {
+//!     #[serde(with = "common::util::datetime::millis")]
+//!     updated_at: DateTime<Utc>,
+//!     #[serde(default, with = "common::util::datetime::lenient::option")]
+//!     deleted_at: Option<DateTime<Utc>>,
+//! }
+//! ```
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::de::Error;
+
+/// Integers below this are treated as seconds by [`lenient`], anything else as milliseconds.
+///
+/// As seconds it's the year 5138, as milliseconds it's March 1973.
+pub const LENIENT_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+pub fn from_millis<E: Error>(value: i64) -> Result<DateTime<Utc>, E> {
+    Utc.timestamp_millis_opt(value)
+        .single()
+        .ok_or_else(|| E::custom(format!("timestamp in milliseconds out of range: {value}")))
+}
+
+pub fn from_seconds<E: Error>(value: i64) -> Result<DateTime<Utc>, E> {
+    Utc.timestamp_opt(value, 0)
+        .single()
+        .ok_or_else(|| E::custom(format!("timestamp in seconds out of range: {value}")))
+}
+
+pub fn from_rfc3339<E: Error>(value: &str) -> Result<DateTime<Utc>, E> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|v| v.with_timezone(&Utc))
+        .map_err(|e| E::custom(format!("invalid RFC 3339 date \"{value}\": {e}")))
+}
+
+macro_rules! impl_optional {
+    () => {
+        /// `Option<DateTime<Utc>>` variant.
+        pub mod option {
+            use chrono::{DateTime, Utc};
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S>(value: &Option<DateTime<Utc>>, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                match value {
+                    Some(v) => super::serialize(v, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D>(value: D) -> Result<Option<DateTime<Utc>>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Ok(Option::<Wrapper>::deserialize(value)?.map(|v| v.0))
+            }
+
+            struct Wrapper(DateTime<Utc>);
+
+            impl<'de> Deserialize<'de> for Wrapper {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    super::deserialize(deserializer).map(Self)
+                }
+            }
+        }
+
+        /// `Option<Option<DateTime<Utc>>>` variant. Requires `#[serde(default)]` on the field.
+        pub mod option_option {
+            use chrono::{DateTime, Utc};
+            use serde::{Deserializer, Serializer};
+
+            pub fn serialize<S>(
+                value: &Option<Option<DateTime<Utc>>>,
+                s: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                match value {
+                    Some(v) => super::option::serialize(v, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D>(value: D) -> Result<Option<Option<DateTime<Utc>>>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                // Only called when the field exists. A missing field is handled by `#[serde(default)]`.
+                super::option::deserialize(value).map(Some)
+            }
+        }
+    };
+}
+
+/// Milliseconds since the Unix epoch. This is what the rest of the crate uses.
+pub mod millis {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_i64(value.timestamp_millis())
+    }
+
+    pub fn deserialize<'de, D>(value: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::from_millis(i64::deserialize(value)?)
+    }
+
+    impl_optional!();
+}
+
+/// Seconds since the Unix epoch. Sub-second precision is dropped.
+pub mod seconds {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_i64(value.timestamp())
+    }
+
+    pub fn deserialize<'de, D>(value: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::from_seconds(i64::deserialize(value)?)
+    }
+
+    impl_optional!();
+}
+
+/// RFC 3339 / ISO 8601 string, eg. `2023-04-01T12:30:00Z`.
+pub mod rfc3339 {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&value.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    pub fn deserialize<'de, D>(value: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::from_rfc3339(&String::deserialize(value)?)
+    }
+
+    impl_optional!();
+}
+
+/// Serializes as milliseconds. Deserializes milliseconds, seconds, RFC 3339 strings and numeric strings.
+///
+/// Integers are told apart with [`LENIENT_MILLIS_THRESHOLD`](super::LENIENT_MILLIS_THRESHOLD).
+pub mod lenient {
+    use std::fmt;
+
+    use chrono::{DateTime, Utc};
+    use serde::{
+        de::{Error, Visitor},
+        Deserializer, Serializer,
+    };
+
+    use super::LENIENT_MILLIS_THRESHOLD;
+
+    pub fn serialize<S>(value: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::millis::serialize(value, s)
+    }
+
+    pub fn deserialize<'de, D>(value: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        value.deserialize_any(LenientVisitor)
+    }
+
+    fn from_number<E: Error>(value: i64) -> Result<DateTime<Utc>, E> {
+        if value.abs() < LENIENT_MILLIS_THRESHOLD {
+            super::from_seconds(value)
+        } else {
+            super::from_millis(value)
+        }
+    }
+
+    struct LenientVisitor;
+
+    impl<'de> Visitor<'de> for LenientVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a timestamp in seconds or milliseconds, or an RFC 3339 string")
+        }
+
+        fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+            from_number(v)
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+            let v =
+                i64::try_from(v).map_err(|_| E::custom(format!("timestamp out of range: {v}")))?;
+
+            from_number(v)
+        }
+
+        fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+            if !v.is_finite() || v.abs() >= i64::MAX as f64 {
+                return Err(E::custom(format!("timestamp out of range: {v}")));
+            }
+
+            from_number(v.trunc() as i64)
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            match v.trim().parse::<i64>() {
+                Ok(v) => from_number(v),
+                Err(_) => super::from_rfc3339(v),
+            }
+        }
+    }
+
+    impl_optional!();
+}
@@ -1,25 +1,12 @@
-use chrono::{DateTime, NaiveDate, TimeZone, Utc};
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
 
-/// Truncate string based off of char indices instead of bytes.
-pub fn truncate_on_indices(s: &mut String, max_chars: usize) {
-    if let Some((new_len, _)) = s.char_indices().nth(max_chars) {
-        s.truncate(new_len);
-    }
-}
-
-pub fn upper_case_first_char(mut value: String) -> String {
-    // Get the first char
-    if let Some(v) = value.chars().next() {
-        // Uppercase first char
-        let first = v.to_uppercase().to_string();
-
-        // Replace first char with uppercase one.
-        value.replace_range(0..v.len_utf8(), &first);
-    }
+pub mod datetime;
+pub mod patch;
+mod text;
 
-    value
-}
+pub use patch::{apply_required, patch_from_field_map, ApplyPatch, Patch};
+pub use text::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LoadingItem<V> {
@@ -32,7 +19,11 @@ pub use frontend::*;
 
 #[cfg(feature = "frontend")]
 mod frontend {
-    use web_sys::Element;
+    use wasm_bindgen::JsCast;
+    use web_sys::{Element, HtmlElement};
+
+    /// Elements which can receive keyboard focus.
+    static FOCUSABLE_SELECTOR: &str = "a[href], area[href], button:not([disabled]), input:not([disabled]):not([type=\"hidden\"]), select:not([disabled]), textarea:not([disabled]), iframe, [contenteditable=\"true\"], [tabindex]:not([tabindex=\"-1\"])";
 
     pub fn does_parent_contain_class(element: &Element, value: &str) -> bool {
         if element.class_list().contains(value) {
@@ -53,15 +44,30 @@ mod frontend {
             false
         }
     }
+
+    /// Focusable descendants of the element in document order.
+    pub fn get_focusable_elements(container: &Element) -> Vec<HtmlElement> {
+        let Ok(list) = container.query_selector_all(FOCUSABLE_SELECTOR) else {
+            return Vec::new();
+        };
+
+        (0..list.length())
+            .filter_map(|i| list.item(i))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            // Skip hidden elements.
+            .filter(|v| v.offset_parent().is_some() || v.get_client_rects().length() != 0)
+            .collect()
+    }
 }
 
 // DateTime
+// Kept for existing `serialize_with`/`deserialize_with` attributes. See [`datetime`] for the `with` modules.
 
 pub fn serialize_datetime<S>(value: &DateTime<Utc>, s: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    s.serialize_i64(value.timestamp_millis())
+    datetime::millis::serialize(value, s)
 }
 
 pub fn serialize_datetime_opt<S>(
@@ -71,10 +77,7 @@ pub fn serialize_datetime_opt<S>(
 where
     S: Serializer,
 {
-    match value {
-        Some(v) => s.serialize_i64(v.timestamp_millis()),
-        None => s.serialize_none(),
-    }
+    datetime::millis::option::serialize(value, s)
 }
 
 pub fn serialize_datetime_opt_opt<S>(
@@ -84,14 +87,14 @@ pub fn serialize_datetime_opt_opt<S>(
 where
     S: Serializer,
 {
-    value.map(|v| v.map(|v| v.timestamp_millis())).serialize(s)
+    datetime::millis::option_option::serialize(value, s)
 }
 
 pub fn deserialize_datetime<'de, D>(value: D) -> std::result::Result<DateTime<Utc>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    Ok(Utc.timestamp_millis_opt(i64::deserialize(value)?).unwrap())
+    datetime::millis::deserialize(value)
 }
 
 pub fn deserialize_datetime_opt<'de, D>(
@@ -100,33 +103,27 @@ pub fn deserialize_datetime_opt<'de, D>(
 where
     D: Deserializer<'de>,
 {
-    if let Some(v) = Option::<i64>::deserialize(value)? {
-        Ok(Some(Utc.timestamp_millis_opt(v).unwrap()))
-    } else {
-        Ok(None)
-    }
+    datetime::millis::option::deserialize(value)
 }
 
+/// Requires `#[serde(default)]` on the field. A `null` value becomes `Some(None)`.
 pub fn deserialize_datetime_opt_opt<'de, D>(
     value: D,
 ) -> std::result::Result<Option<Option<DateTime<Utc>>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    if let Some(v) = Option::<Option<i64>>::deserialize(value)? {
-        Ok(Some(v.map(|v| Utc.timestamp_millis_opt(v).unwrap())))
-    } else {
-        Ok(None)
-    }
+    datetime::millis::option_option::deserialize(value)
 }
 
 // Date
+// Unlike DateTime these are stored in seconds.
 
 pub fn serialize_date<S>(value: &NaiveDate, s: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    s.serialize_i64(value.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
+    s.serialize_i64(value.and_time(NaiveTime::MIN).and_utc().timestamp())
 }
 
 pub fn serialize_naivedate_opt<S>(
@@ -137,7 +134,7 @@ where
     S: Serializer,
 {
     match value {
-        Some(v) => s.serialize_i64(v.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp()),
+        Some(v) => serialize_date(v, s),
         None => s.serialize_none(),
     }
 }
@@ -146,10 +143,7 @@ pub fn deserialize_date<'de, D>(value: D) -> std::result::Result<NaiveDate, D::E
 where
     D: Deserializer<'de>,
 {
-    Ok(Utc
-        .timestamp_opt(i64::deserialize(value)?, 0)
-        .unwrap()
-        .date_naive())
+    Ok(datetime::from_seconds(i64::deserialize(value)?)?.date_naive())
 }
 
 pub fn deserialize_naivedate_opt<'de, D>(
@@ -159,7 +153,7 @@ where
     D: Deserializer<'de>,
 {
     if let Some(v) = Option::<i64>::deserialize(value)? {
-        Ok(Some(Utc.timestamp_opt(v, 0).unwrap().date_naive()))
+        Ok(Some(datetime::from_seconds(v)?.date_naive()))
     } else {
         Ok(None)
     }
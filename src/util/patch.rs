@@ -0,0 +1,177 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A field in a partial update.
+///
+/// Use with `#[serde(default, skip_serializing_if = "Patch::is_missing")]` so a missing field
+/// stays [`Patch::Missing`] while an explicit `null` becomes [`Patch::Null`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Patch<T> {
+    /// Leave the current value alone.
+    #[default]
+    Missing,
+    /// Unset the current value.
+    Null,
+    /// Replace the current value.
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn is_value(&self) -> bool {
+        matches!(self, Self::Value(_))
+    }
+
+    pub fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Self::Missing => Patch::Missing,
+            Self::Null => Patch::Null,
+            Self::Value(v) => Patch::Value(v),
+        }
+    }
+
+    pub fn map<N, F: FnOnce(T) -> N>(self, func: F) -> Patch<N> {
+        match self {
+            Self::Missing => Patch::Missing,
+            Self::Null => Patch::Null,
+            Self::Value(v) => Patch::Value(func(v)),
+        }
+    }
+
+    pub fn value(self) -> Option<T> {
+        match self {
+            Self::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn into_option(self) -> Option<Option<T>> {
+        match self {
+            Self::Missing => None,
+            Self::Null => Some(None),
+            Self::Value(v) => Some(Some(v)),
+        }
+    }
+
+    /// Merges the patch into an optional value. Returns if the value was changed.
+    pub fn apply(self, target: &mut Option<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        match self {
+            Self::Missing => false,
+            Self::Null => target.take().is_some(),
+            Self::Value(v) => {
+                if target.as_ref() == Some(&v) {
+                    false
+                } else {
+                    *target = Some(v);
+                    true
+                }
+            }
+        }
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Patch<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            None => Self::Missing,
+            Some(None) => Self::Null,
+            Some(Some(v)) => Self::Value(v),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    /// `None` is treated as [`Patch::Null`].
+    fn from(value: Option<T>) -> Self {
+        value.map(Self::Value).unwrap_or(Self::Null)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Only called when the field exists. A missing field is handled by `#[serde(default)]`.
+        Ok(Option::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Value(v) => serializer.serialize_some(v),
+            Self::Missing | Self::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Merges a partial update into a full value.
+pub trait ApplyPatch {
+    type Patch;
+
+    /// Returns if anything was changed.
+    fn apply_patch(&mut self, patch: Self::Patch) -> bool;
+}
+
+/// Merges a value into a field which can't be unset. Returns if the value was changed.
+pub fn apply_required<T: PartialEq>(value: Option<T>, target: &mut T) -> bool {
+    match value {
+        Some(v) if *target != v => {
+            *target = v;
+            true
+        }
+
+        _ => false,
+    }
+}
+
+/// Creates a patch struct from a map of field names to values, eg. the `PopupComparison` submit map.
+///
+/// Fields which aren't in the map are left as [`Patch::Missing`].
+pub fn patch_from_field_map<P: DeserializeOwned>(
+    map: HashMap<&str, serde_json::Value>,
+) -> serde_json::Result<P> {
+    serde_json::from_value(serde_json::Value::Object(
+        map.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+    ))
+}
+
+/// `Patch<NaiveDate>` stored in seconds, matching [`serialize_date`](super::serialize_date).
+pub mod naivedate {
+    use chrono::NaiveDate;
+    use serde::{Deserializer, Serializer};
+
+    use super::Patch;
+
+    pub fn serialize<S>(value: &Patch<NaiveDate>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Patch::Value(v) => super::super::serialize_date(v, s),
+            Patch::Missing | Patch::Null => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(value: D) -> Result<Patch<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(super::super::deserialize_naivedate_opt(value)?.into())
+    }
+}
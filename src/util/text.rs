@@ -0,0 +1,414 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+static ELLIPSIS: char = '…';
+
+/// Words which stay lowercase in [`title_case`] unless they start or end the title.
+pub static TITLE_SMALL_WORDS: [&str; 27] = [
+    "a", "an", "and", "as", "at", "but", "by", "de", "en", "for", "from", "if", "in", "into",
+    "nor", "of", "off", "on", "or", "per", "so", "the", "to", "up", "via", "vs", "yet",
+];
+
+/// Truncate string based off of grapheme clusters instead of bytes.
+///
+/// Emoji and characters with combining marks count as one and are never split.
+pub fn truncate_on_indices(s: &mut String, max_chars: usize) {
+    if let Some((new_len, _)) = s.grapheme_indices(true).nth(max_chars) {
+        s.truncate(new_len);
+    }
+}
+
+pub fn upper_case_first_char(mut value: String) -> String {
+    // Get the first char
+    if let Some(v) = value.chars().next() {
+        // Uppercase first char
+        let first = v.to_uppercase().to_string();
+
+        // Replace first char with uppercase one.
+        value.replace_range(0..v.len_utf8(), &first);
+    }
+
+    value
+}
+
+/// Shortens the string to at most `max_graphemes` grapheme clusters, ending with an ellipsis.
+///
+/// Prefers cutting at the end of a word as long as it doesn't remove more than a fifth of the string.
+pub fn truncate_with_ellipsis(value: &str, max_graphemes: usize) -> String {
+    let graphemes = value.graphemes(true).collect::<Vec<_>>();
+
+    if graphemes.len() <= max_graphemes {
+        return value.to_string();
+    }
+
+    if max_graphemes == 0 {
+        return String::new();
+    }
+
+    // Leave room for the ellipsis.
+    let mut end = max_graphemes - 1;
+
+    // If we're cutting into a word, try to back up to the previous word boundary.
+    if !is_whitespace(graphemes[end]) {
+        let min_end = end - end / 5;
+
+        if let Some(pos) = (min_end..end).rev().find(|&i| is_whitespace(graphemes[i])) {
+            end = pos;
+        }
+    }
+
+    let mut compiled = graphemes[..end].concat();
+
+    let trimmed_len = compiled
+        .trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '.'))
+        .len();
+    compiled.truncate(trimmed_len);
+
+    compiled.push(ELLIPSIS);
+
+    compiled
+}
+
+/// Capitalizes each word except for [`TITLE_SMALL_WORDS`] in the middle of the title.
+///
+/// Words with capitals after the first letter (eg. "iPhone", "McCoy") are left as they are.
+/// A title in all capitals is lowercased first.
+pub fn title_case(value: &str) -> String {
+    let is_all_caps =
+        value.chars().any(|c| c.is_alphabetic()) && !value.chars().any(|c| c.is_lowercase());
+
+    let words = value.split_word_bounds().collect::<Vec<_>>();
+
+    let first_word = words.iter().position(|v| is_word(v));
+    let last_word = words.iter().rposition(|v| is_word(v));
+
+    let mut compiled = String::with_capacity(value.len());
+    let mut after_break = true;
+
+    for (i, &word) in words.iter().enumerate() {
+        if !is_word(word) {
+            // Start a new "title" after a colon, dash or opening bracket. eg. "Dune: the Sequel"
+            if word
+                .chars()
+                .any(|c| matches!(c, ':' | '—' | '–' | '(' | '[' | '?' | '!'))
+            {
+                after_break = true;
+            }
+
+            compiled.push_str(word);
+            continue;
+        }
+
+        let word = if is_all_caps {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        };
+
+        let has_inner_caps = word.chars().skip(1).any(|c| c.is_uppercase());
+        let is_small = TITLE_SMALL_WORDS.contains(&word.to_lowercase().as_str());
+
+        if has_inner_caps {
+            compiled.push_str(&word);
+        } else if is_small && !after_break && Some(i) != first_word && Some(i) != last_word {
+            compiled.push_str(&word.to_lowercase());
+        } else {
+            compiled.push_str(&upper_case_first_char(word));
+        }
+
+        after_break = false;
+    }
+
+    compiled
+}
+
+/// Creates a URL safe slug. Non-ASCII characters are transliterated. eg. "Les Misérables" -> "les-miserables"
+pub fn slugify(value: &str) -> String {
+    let ascii = deunicode::deunicode(&strip_html(value));
+
+    let mut compiled = String::with_capacity(ascii.len());
+
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            compiled.push(c.to_ascii_lowercase());
+        } else if !compiled.is_empty() && !compiled.ends_with('-') && c != '\'' {
+            compiled.push('-');
+        }
+    }
+
+    let trimmed_len = compiled.trim_end_matches('-').len();
+    compiled.truncate(trimmed_len);
+
+    compiled
+}
+
+/// Parses a roman numeral written in its standard form, ignoring case. eg. "XIV" -> 14
+///
+/// Non-standard forms such as "IIII" or "IL", and words such as "Ill", return `None`.
+pub fn parse_roman_numeral(value: &str) -> Option<u32> {
+    static NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let upper = value.to_ascii_uppercase();
+
+    let mut rest = upper.as_str();
+    let mut total = 0;
+
+    for (number, numeral) in NUMERALS {
+        while let Some(stripped) = rest.strip_prefix(numeral) {
+            rest = stripped;
+            total += number;
+        }
+    }
+
+    if !rest.is_empty() || total == 0 {
+        return None;
+    }
+
+    // Write it back out to reject non-standard forms. eg. "IIII" or "IXI"
+    let mut standard = String::with_capacity(upper.len());
+    let mut remaining = total;
+
+    for (number, numeral) in NUMERALS {
+        while remaining >= number {
+            standard.push_str(numeral);
+            remaining -= number;
+        }
+    }
+
+    (standard == upper).then_some(total)
+}
+
+/// Removes HTML tags and decodes entities. Block level tags are turned into line breaks.
+///
+/// Used for descriptions returned from metadata agents. Not a sanitizer.
+pub fn strip_html(value: &str) -> String {
+    let mut compiled = String::with_capacity(value.len());
+
+    let mut rest = value;
+
+    while let Some(start) = rest.find(['<', '&']) {
+        compiled.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with('&') {
+            match decode_entity(rest) {
+                Some((c, len)) => {
+                    compiled.push(c);
+                    rest = &rest[len..];
+                }
+
+                None => {
+                    compiled.push('&');
+                    rest = &rest[1..];
+                }
+            }
+
+            continue;
+        }
+
+        // Comments
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map(|i| &comment[i + 3..]).unwrap_or("");
+            continue;
+        }
+
+        // Only a tag if a name, '/' or '!' follows. eg. "x < y and z > w" is text.
+        let is_tag_start =
+            rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+
+        let Some(end) = find_tag_end(rest).filter(|_| is_tag_start) else {
+            compiled.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        // Declarations such as "<!DOCTYPE html>" have a name starting with '!' and are dropped below.
+        let is_closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match name.as_str() {
+            // Skip the contents entirely.
+            "script" | "style" if !is_closing => {
+                let closing = format!("</{name}");
+
+                rest = find_ascii_case_insensitive(rest, &closing)
+                    .and_then(|i| rest[i..].find('>').map(|e| &rest[i + e + 1..]))
+                    .unwrap_or("");
+            }
+
+            "br" => compiled.push('\n'),
+
+            "p" | "div" | "li" | "ul" | "ol" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            | "blockquote" | "section" | "article"
+                if !compiled.is_empty() && !compiled.ends_with('\n') =>
+            {
+                compiled.push('\n');
+            }
+
+            _ => (),
+        }
+    }
+
+    compiled.push_str(rest);
+
+    // Collapse whitespace while keeping line breaks.
+    compiled
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Index of the '>' closing the tag, skipping quoted attribute values. eg. `<a title="a > b">`
+fn find_tag_end(value: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut previous = ' ';
+
+    for (i, c) in value.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+
+            None if c == '>' => return Some(i),
+            None if (c == '"' || c == '\'') && previous == '=' => quote = Some(c),
+            None => (),
+        }
+
+        if !c.is_whitespace() {
+            previous = c;
+        }
+    }
+
+    // Unterminated quote. Fall back to the first '>'.
+    value.find('>')
+}
+
+fn is_whitespace(value: &str) -> bool {
+    value.chars().all(char::is_whitespace)
+}
+
+fn is_word(value: &str) -> bool {
+    value.chars().any(char::is_alphanumeric)
+}
+
+fn find_ascii_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|v| v.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Returns the decoded character and the length of the entity.
+fn decode_entity(value: &str) -> Option<(char, usize)> {
+    let end = value.char_indices().take(12).find(|(_, c)| *c == ';')?.0;
+    let entity = &value[1..end];
+
+    let c = if let Some(num) = entity.strip_prefix('#') {
+        let code = if let Some(hex) = num.strip_prefix(['x', 'X']) {
+            u32::from_str_radix(hex, 16).ok()?
+        } else {
+            num.parse().ok()?
+        };
+
+        char::from_u32(code)?
+    } else {
+        match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" => ' ',
+            "ndash" => '–',
+            "mdash" => '—',
+            "hellip" => '…',
+            "lsquo" => '‘',
+            "rsquo" => '’',
+            "ldquo" => '“',
+            "rdquo" => '”',
+            "copy" => '©',
+            "reg" => '®',
+            "trade" => '™',
+            _ => return None,
+        }
+    };
+
+    Some((c, end + 1))
+}
+
+/// Normalized Levenshtein similarity between two strings, from `0.0` (different) to `1.0` (equal).
+///
+/// Compares characters, so callers should normalize case and accents first if they don't matter.
+pub fn similarity(left: &str, right: &str) -> f64 {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+
+    let max_len = left.len().max(right.len());
+
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    let mut prev_row = (0..=right.len()).collect::<Vec<_>>();
+    let mut curr_row = vec![0; right.len() + 1];
+
+    for (i, lc) in left.iter().enumerate() {
+        curr_row[0] = i + 1;
+
+        for (j, rc) in right.iter().enumerate() {
+            let cost = usize::from(lc != rc);
+
+            curr_row[j + 1] = (prev_row[j + 1] + 1)
+                .min(curr_row[j] + 1)
+                .min(prev_row[j] + cost);
+        }
+
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    1.0 - prev_row[right.len()] as f64 / max_len as f64
+}
+
+/// Lowercases, transliterates to ASCII and keeps only letters, digits and single spaces.
+///
+/// Used before comparing names and titles. eg. "Brontë, C." -> "bronte c"
+pub fn normalize_for_comparison(value: &str) -> String {
+    let ascii = deunicode::deunicode(value);
+
+    let mut compiled = String::with_capacity(ascii.len());
+
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            compiled.push(c.to_ascii_lowercase());
+        } else if c != '\'' && !compiled.is_empty() && !compiled.ends_with(' ') {
+            compiled.push(' ');
+        }
+    }
+
+    let trimmed_len = compiled.trim_end().len();
+    compiled.truncate(trimmed_len);
+
+    compiled
+}
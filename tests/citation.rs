@@ -0,0 +1,264 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::{
+    api::librarian::{PublicAuthor, PublicBook},
+    citation_keys, escape_bibtex, from_csl_json, to_bibtex, to_csl_json, to_marc_json, to_ris,
+    CitationEntry,
+};
+
+fn author(id: usize, name: &str) -> PublicAuthor {
+    PublicAuthor {
+        id,
+        name: name.to_string(),
+        description: None,
+        birth_date: None,
+        thumb_url: None,
+        other_names: Vec::new(),
+        updated_at: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+        created_at: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+        deleted_at: None,
+    }
+}
+
+fn book(id: usize, title: &str, author_ids: Vec<usize>) -> PublicBook {
+    let now = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    PublicBook {
+        id,
+        title: Some(title.to_string()),
+        clean_title: None,
+        description: Some(String::from("A hobbit's\ntale & 100% fun")),
+        rating: 0.0,
+        thumb_url: None,
+        isbns: Some(vec![String::from("9780547928227")]),
+        display_author_id: None,
+        publisher: Some(String::from("Allen & Unwin")),
+        author_ids,
+        is_public: true,
+        edition_count: 0,
+        available_at: NaiveDate::from_ymd_opt(1937, 9, 21),
+        language: 0,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    }
+}
+
+#[test]
+fn citation_keys_are_unique() {
+    let authors = [author(1, "J. R. R. Tolkien"), author(2, "Stephen King")];
+
+    let books = [
+        book(1, "The Hobbit", vec![1]),
+        book(2, "The Hobbit", vec![1]),
+        book(3, "It", vec![2]),
+    ];
+
+    let entries = books
+        .iter()
+        .map(|v| CitationEntry::new(v, &authors))
+        .collect::<Vec<_>>();
+
+    assert_eq!(entries[0].citation_key(), "tolkien1937hobbit");
+    assert_eq!(
+        citation_keys(&entries),
+        vec!["tolkien1937hobbita", "tolkien1937hobbitb", "king1937it"]
+    );
+}
+
+#[test]
+fn citation_keys_skip_used_keys() {
+    let authors = [author(1, "Stephen King")];
+
+    // "It" twice and a book whose key is the first suffixed one.
+    let books = [
+        book(1, "It", vec![1]),
+        book(2, "It", vec![1]),
+        book(3, "Ita", vec![1]),
+    ];
+
+    let entries = books
+        .iter()
+        .map(|v| CitationEntry::new(v, &authors))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        citation_keys(&entries),
+        vec!["king1937itb", "king1937itc", "king1937ita"]
+    );
+}
+
+#[test]
+fn many_duplicate_citation_keys() {
+    let authors = [author(1, "Stephen King")];
+    let books = (0..300)
+        .map(|id| book(id, "It", vec![1]))
+        .collect::<Vec<_>>();
+
+    let entries = books
+        .iter()
+        .map(|v| CitationEntry::new(v, &authors))
+        .collect::<Vec<_>>();
+
+    let keys = citation_keys(&entries);
+
+    assert_eq!(keys[25], "king1937itz");
+    assert_eq!(keys[26], "king1937itaa");
+    assert_eq!(keys[27], "king1937itab");
+    assert_eq!(keys[299], "king1937itkn");
+
+    let unique = keys.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(unique.len(), keys.len());
+}
+
+#[test]
+fn bibtex_export() {
+    let authors = [
+        author(1, "J. R. R. Tolkien"),
+        author(2, "Christopher Tolkien"),
+    ];
+    let books = [book(1, "The Hobbit {Annotated}", vec![1, 2])];
+    let entries = vec![CitationEntry::new(&books[0], &authors)];
+
+    let bibtex = to_bibtex(&entries);
+
+    assert!(bibtex.starts_with("@book{tolkien1937hobbit,\n"));
+    assert!(bibtex.contains("  title = {The Hobbit \\{Annotated\\}},\n"));
+    assert!(bibtex.contains("  author = {Tolkien, J. R. R. and Tolkien, Christopher},\n"));
+    assert!(bibtex.contains("  publisher = {Allen \\& Unwin},\n"));
+    assert!(bibtex.contains("  year = {1937},\n"));
+    assert!(bibtex.contains("  isbn = {9780547928227},\n"));
+    assert!(bibtex.contains("  abstract = {A hobbit's tale \\& 100\\% fun},\n"));
+    assert!(bibtex.contains("  month = sep,\n"));
+    assert!(bibtex.ends_with("}\n"));
+
+    assert_eq!(
+        escape_bibtex("a_b~c\\"),
+        "a\\_b\\textasciitilde{}c\\textbackslash{}"
+    );
+}
+
+#[test]
+fn ris_export() {
+    let authors = [author(1, "J. R. R. Tolkien")];
+    let books = [book(1, "The Hobbit", vec![1])];
+    let entries = vec![CitationEntry::new(&books[0], &authors)];
+
+    let ris = to_ris(&entries);
+    let lines = ris.split("\r\n").collect::<Vec<_>>();
+
+    assert_eq!(lines[0], "TY  - BOOK");
+    assert!(lines.contains(&"AU  - Tolkien, J. R. R."));
+    assert!(lines.contains(&"DA  - 1937/09/21"));
+    assert!(lines.contains(&"SN  - 9780547928227"));
+    assert!(lines.contains(&"AB  - A hobbit's tale & 100% fun"));
+    assert_eq!(lines[lines.len() - 2], "ER  - ");
+}
+
+#[test]
+fn csl_json_round_trip() {
+    let authors = [author(1, "J. R. R. Tolkien")];
+    let books = [book(1, "The Hobbit", vec![1])];
+    let entries = vec![CitationEntry::new(&books[0], &authors)];
+
+    let items = to_csl_json(&entries);
+    let json = serde_json::to_string(&items).unwrap();
+
+    assert!(json.contains(r#""ISBN":"9780547928227""#));
+    assert!(json.contains(r#""date-parts":[[1937,9,21]]"#));
+
+    let imported = from_csl_json(&json).unwrap();
+    assert_eq!(imported, items);
+
+    let book = imported[0].to_public_book(Utc::now());
+
+    assert_eq!(book.title.as_deref(), Some("The Hobbit"));
+    assert_eq!(book.isbns, Some(vec![String::from("9780547928227")]));
+    assert_eq!(book.available_at, NaiveDate::from_ymd_opt(1937, 9, 21));
+    assert_eq!(imported[0].author_names(), vec!["J. R. R. Tolkien"]);
+}
+
+#[test]
+fn csl_json_import_from_other_tools() {
+    let items = from_csl_json(
+        r#"{
+            "id": 12,
+            "type": "book",
+            "title": "Les Misérables",
+            "author": [{ "family": "Hugo", "given": "Victor" }, { "literal": "Penguin Editors" }],
+            "issued": { "date-parts": [["1862", "4"]] },
+            "ISBN": "0-14-044430-0",
+            "language": "fr-FR"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(items[0].id, "12");
+
+    let book = items[0].to_public_book(Utc::now());
+
+    assert_eq!(book.isbns, Some(vec![String::from("9780140444308")]));
+    assert_eq!(book.available_at, NaiveDate::from_ymd_opt(1862, 4, 1));
+    assert_eq!(book.language, 1);
+    assert_eq!(
+        items[0].author_names(),
+        vec!["Victor Hugo", "Penguin Editors"]
+    );
+
+    assert!(from_csl_json("[{}]").is_err());
+}
+
+#[test]
+fn marc_json_export() {
+    let authors = [
+        author(1, "J. R. R. Tolkien"),
+        author(2, "Christopher Tolkien"),
+    ];
+    let books = [book(1, "The Hobbit", vec![1, 2])];
+    let entries = vec![CitationEntry::new(&books[0], &authors)];
+
+    let records = to_marc_json(&entries);
+    let record = &records[0];
+
+    assert_eq!(record.leader.len(), 24);
+
+    let field = |tag: &str| record.fields.iter().find(|v| v.tag() == tag).unwrap();
+
+    assert_eq!(
+        field("020").subfield('a').collect::<Vec<_>>(),
+        vec!["9780547928227"]
+    );
+    assert_eq!(field("041").subfield('a').next(), Some("eng"));
+    assert_eq!(field("100").subfield('a').next(), Some("Tolkien, J. R. R."));
+    assert_eq!(
+        field("700").subfield('a').next(),
+        Some("Tolkien, Christopher")
+    );
+    assert_eq!(field("264").subfield('c').next(), Some("1937"));
+
+    let json = serde_json::to_value(record).unwrap();
+
+    assert_eq!(
+        json["fields"][0],
+        serde_json::json!({ "001": "tolkien1937hobbit" })
+    );
+    assert!(json["fields"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({
+            "245": {
+                "ind1": "1",
+                "ind2": "4",
+                "subfields": [{ "a": "The Hobbit" }],
+            }
+        })));
+    assert!(json["fields"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({
+            "264": {
+                "ind1": " ",
+                "ind2": "1",
+                "subfields": [{ "b": "Allen & Unwin" }, { "c": "1937" }],
+            }
+        })));
+}
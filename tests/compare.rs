@@ -0,0 +1,170 @@
+#![cfg(feature = "frontend")]
+
+use common::component::popup::compare::{
+    morph_map_value, CompareColumn, CompareContainer, CompareDisplay, FieldSelection, MapContainer,
+    SelectedSide,
+};
+use serde_json::json;
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+fn container() -> CompareContainer {
+    let mut left = MapContainer::new();
+    left.insert(
+        "title",
+        morph_map_value(String::from("The Hobbit")).unwrap(),
+    );
+    left.insert(
+        "isbns",
+        morph_map_value(strings(&["9780261103344", "0261103342"])).unwrap(),
+    );
+
+    let mut right = MapContainer::new();
+    right.insert("title", morph_map_value(String::from("Hobbit")).unwrap());
+    right.insert(
+        "isbns",
+        morph_map_value(strings(&["0261103342", "9780547928227"])).unwrap(),
+    );
+    right.insert("tags", morph_map_value(strings(&["Fantasy"])).unwrap());
+
+    CompareContainer::create(
+        vec![
+            ("title", "Title", CompareDisplay::Text),
+            ("isbns", "ISBNs", CompareDisplay::Text),
+            ("tags", "Tags", CompareDisplay::Text),
+        ],
+        left,
+        right,
+    )
+}
+
+#[test]
+fn only_arrays_are_mergeable() {
+    let compare = container();
+
+    assert!(compare.is_field_mergeable("isbns"));
+    // Missing on the left.
+    assert!(compare.is_field_mergeable("tags"));
+    assert!(!compare.is_field_mergeable("title"));
+    assert!(!compare.is_field_mergeable("missing"));
+}
+
+#[test]
+fn merging_everything_removes_duplicates() {
+    let compare = container();
+
+    let selection = FieldSelection::Items(vec![vec![true, true], vec![true, true]]);
+
+    assert_eq!(
+        compare.get_field_value("isbns", &selection),
+        Some(json!(["9780261103344", "0261103342", "9780547928227"]))
+    );
+}
+
+#[test]
+fn merging_ticked_items() {
+    let compare = container();
+
+    let selection = FieldSelection::Items(vec![vec![true, false], vec![false, true]]);
+
+    assert_eq!(
+        compare.get_field_value("isbns", &selection),
+        Some(json!(["9780261103344", "9780547928227"]))
+    );
+
+    let selection = FieldSelection::Items(vec![Vec::new(), vec![true]]);
+
+    assert_eq!(
+        compare.get_field_value("tags", &selection),
+        Some(json!(["Fantasy"]))
+    );
+
+    // Items can't be picked from a single value.
+    assert_eq!(compare.get_field_value("title", &selection), None);
+}
+
+#[test]
+fn whole_side_selection() {
+    let compare = container();
+
+    let selection = FieldSelection::from(SelectedSide::Right);
+
+    assert_eq!(
+        compare.get_field_value("title", &selection),
+        Some(json!("Hobbit"))
+    );
+    assert!(selection.is_item_selected(1, 1));
+    assert!(!selection.is_item_selected(0, 0));
+
+    assert_eq!(
+        compare.get_field_value("tags", &FieldSelection::from(SelectedSide::Left)),
+        None
+    );
+}
+
+fn candidates() -> CompareContainer {
+    let map = |title: &str, isbns: &[&str]| {
+        let mut map = MapContainer::new();
+        map.insert("title", morph_map_value(title.to_string()).unwrap());
+        map.insert("isbns", morph_map_value(strings(isbns)).unwrap());
+        map
+    };
+
+    CompareContainer::create_multi(
+        vec![
+            ("title", "Title", CompareDisplay::Text),
+            ("isbns", "ISBNs", CompareDisplay::Text),
+        ],
+        vec![
+            (
+                CompareColumn::new("Current"),
+                map("The Hobbit", &["0261103342"]),
+            ),
+            (CompareColumn::new("Google"), map("Hobbit", &["0261103342"])),
+            (
+                CompareColumn::new("OpenLibrary"),
+                map("Hobbit", &["9780547928227"]),
+            ),
+        ],
+    )
+}
+
+#[test]
+fn columns_agree() {
+    let compare = candidates();
+
+    assert_eq!(compare.columns().len(), 3);
+    assert_eq!(compare.columns()[2].title, "OpenLibrary");
+
+    assert_eq!(compare.field_agreement("title", 0), 0);
+    assert_eq!(compare.field_agreement("title", 1), 1);
+    assert_eq!(compare.field_agreement("title", 2), 1);
+    assert_eq!(compare.field_agreement("isbns", 0), 1);
+    assert_eq!(compare.field_agreement("missing", 0), 0);
+}
+
+#[test]
+fn selecting_and_merging_columns() {
+    let compare = candidates();
+
+    assert_eq!(
+        compare.get_field_value("title", &FieldSelection::Column(2)),
+        Some(json!("Hobbit"))
+    );
+    assert_eq!(compare.get_column_value_map(2).len(), 2);
+    assert_eq!(
+        compare.get_field_value("title", &FieldSelection::Column(3)),
+        None
+    );
+
+    let selection = FieldSelection::Items(vec![vec![true], vec![true], vec![true]]);
+
+    assert_eq!(
+        compare.get_field_value("isbns", &selection),
+        Some(json!(["0261103342", "9780547928227"]))
+    );
+    assert!(selection.is_column_partial(2));
+    assert!(!selection.is_column_selected(2));
+}
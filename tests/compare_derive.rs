@@ -0,0 +1,136 @@
+#![cfg(feature = "frontend")]
+
+use common::component::popup::compare::{Comparable, CompareValue, MapValue, SelectedSide};
+use serde::{Deserialize, Serialize};
+
+fn language_name(value: &u16) -> CompareValue {
+    CompareValue::Single(format!("Language #{value}"))
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Comparable)]
+struct BookEdit {
+    #[comparable(skip)]
+    id: i32,
+
+    title: Option<String>,
+    #[comparable(title = "Thumbnail", display = "image")]
+    thumb_url: Option<String>,
+    isbns: Vec<String>,
+    #[comparable(with = "language_name")]
+    language: u16,
+}
+
+fn current() -> BookEdit {
+    BookEdit {
+        id: 5,
+        title: Some(String::from("The Hobbit")),
+        thumb_url: None,
+        isbns: vec![String::from("9780261103344")],
+        language: 1,
+    }
+}
+
+fn candidate() -> BookEdit {
+    BookEdit {
+        id: 0,
+        title: Some(String::from("The Hobbit, or There and Back Again")),
+        thumb_url: Some(String::from("https://example.com/hobbit.jpg")),
+        isbns: vec![String::from("9780261103344"), String::from("0261103342")],
+        language: 2,
+    }
+}
+
+#[test]
+fn create_map_skips_fields_and_empty_options() {
+    let map = current().create_map().unwrap();
+
+    let mut keys = map.keys().copied().collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    assert_eq!(keys, ["isbns", "language", "title"]);
+
+    assert_eq!(
+        map["isbns"],
+        MapValue {
+            display_value: CompareValue::Array(vec![String::from("9780261103344")]),
+            original_value: serde_json::json!(["9780261103344"]),
+        }
+    );
+
+    assert_eq!(
+        map["language"],
+        MapValue {
+            display_value: CompareValue::Single(String::from("Language #1")),
+            original_value: serde_json::json!(1),
+        }
+    );
+}
+
+#[test]
+fn round_trips_through_a_comparison() {
+    let compare = current().create_comparison_with(&candidate()).unwrap();
+
+    let mut names = compare.get_field_names();
+    names.sort_unstable();
+
+    assert_eq!(names, ["isbns", "language", "thumb_url", "title"]);
+
+    let rebuilt =
+        BookEdit::create_from_comparison(compare.get_side_value_map(SelectedSide::Right)).unwrap();
+
+    // Skipped fields fall back to their default.
+    assert_eq!(
+        rebuilt,
+        BookEdit {
+            id: 0,
+            ..candidate()
+        }
+    );
+
+    let rebuilt =
+        BookEdit::create_from_comparison(compare.get_side_value_map(SelectedSide::Left)).unwrap();
+
+    assert_eq!(rebuilt, BookEdit { id: 0, ..current() });
+}
+
+#[test]
+fn missing_required_field_is_an_error() {
+    let mut map = current()
+        .create_comparison_with(&candidate())
+        .unwrap()
+        .get_side_value_map(SelectedSide::Left);
+
+    // Optional fields may be missing.
+    map.remove("title");
+    assert_eq!(
+        BookEdit::create_from_comparison(map.clone()).unwrap().title,
+        None
+    );
+
+    map.remove("language");
+
+    let error = BookEdit::create_from_comparison(map).unwrap_err();
+
+    assert!(error.to_string().contains("language"));
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Comparable)]
+#[comparable(crate = "::common")]
+struct WithCratePath {
+    name: String,
+}
+
+#[test]
+fn crate_path_override() {
+    let value = WithCratePath {
+        name: String::from("Tolkien"),
+    };
+
+    let compare = value.create_comparison_with(&value).unwrap();
+
+    assert_eq!(
+        WithCratePath::create_from_comparison(compare.get_side_value_map(SelectedSide::Left))
+            .unwrap(),
+        value
+    );
+}
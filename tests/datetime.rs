@@ -0,0 +1,200 @@
+use chrono::{DateTime, TimeZone, Utc};
+use common::util::datetime;
+use serde::{Deserialize, Serialize};
+
+fn date() -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(1_680_352_200_123).unwrap()
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Formats {
+    #[serde(with = "datetime::millis")]
+    millis: DateTime<Utc>,
+    #[serde(with = "datetime::seconds")]
+    seconds: DateTime<Utc>,
+    #[serde(with = "datetime::rfc3339")]
+    rfc3339: DateTime<Utc>,
+    #[serde(with = "datetime::lenient")]
+    lenient: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Optional {
+    #[serde(default, with = "datetime::millis::option")]
+    millis: Option<DateTime<Utc>>,
+    #[serde(default, with = "datetime::rfc3339::option")]
+    rfc3339: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Patch {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "datetime::millis::option_option"
+    )]
+    millis: Option<Option<DateTime<Utc>>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "datetime::rfc3339::option_option"
+    )]
+    rfc3339: Option<Option<DateTime<Utc>>>,
+}
+
+#[test]
+fn formats_round_trip() {
+    let value = Formats {
+        millis: date(),
+        seconds: Utc.timestamp_opt(date().timestamp(), 0).unwrap(),
+        rfc3339: date(),
+        lenient: date(),
+    };
+
+    let json = serde_json::to_value(&value).unwrap();
+
+    assert_eq!(json["millis"], 1_680_352_200_123i64);
+    assert_eq!(json["seconds"], 1_680_352_200i64);
+    assert_eq!(json["rfc3339"], "2023-04-01T12:30:00.123Z");
+    assert_eq!(json["lenient"], 1_680_352_200_123i64);
+
+    assert_eq!(serde_json::from_value::<Formats>(json).unwrap(), value);
+}
+
+#[test]
+fn option_round_trip() {
+    for value in [
+        Optional {
+            millis: Some(date()),
+            rfc3339: Some(date()),
+        },
+        Optional {
+            millis: None,
+            rfc3339: None,
+        },
+    ] {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Optional>(&json).unwrap(), value);
+    }
+
+    assert_eq!(
+        serde_json::from_str::<Optional>("{}").unwrap(),
+        Optional {
+            millis: None,
+            rfc3339: None,
+        }
+    );
+}
+
+#[test]
+fn option_option_round_trip() {
+    for value in [
+        Patch {
+            millis: Some(Some(date())),
+            rfc3339: Some(Some(date())),
+        },
+        Patch {
+            millis: Some(None),
+            rfc3339: Some(None),
+        },
+        Patch {
+            millis: None,
+            rfc3339: None,
+        },
+    ] {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Patch>(&json).unwrap(), value);
+    }
+
+    assert_eq!(
+        serde_json::to_string(&Patch {
+            millis: Some(None),
+            rfc3339: None,
+        })
+        .unwrap(),
+        r#"{"millis":null}"#
+    );
+}
+
+#[test]
+fn lenient_accepts_all_formats() {
+    #[derive(Deserialize)]
+    struct Lenient(#[serde(with = "datetime::lenient")] DateTime<Utc>);
+
+    let seconds = Utc.timestamp_opt(date().timestamp(), 0).unwrap();
+
+    for (json, expected) in [
+        ("1680352200123", date()),
+        ("1680352200", seconds),
+        ("1680352200.9", seconds),
+        (r#""1680352200123""#, date()),
+        (r#""2023-04-01T12:30:00.123Z""#, date()),
+        (r#""2023-04-01T14:30:00.123+02:00""#, date()),
+    ] {
+        assert_eq!(
+            serde_json::from_str::<Lenient>(json).unwrap().0,
+            expected,
+            "{json}"
+        );
+    }
+
+    assert!(serde_json::from_str::<Lenient>(r#""yesterday""#).is_err());
+    assert!(serde_json::from_str::<Lenient>("true").is_err());
+}
+
+#[test]
+fn out_of_range_errors() {
+    #[derive(Debug, Deserialize)]
+    struct Millis(
+        #[allow(dead_code)]
+        #[serde(with = "datetime::millis")]
+        DateTime<Utc>,
+    );
+    #[derive(Debug, Deserialize)]
+    struct Seconds(
+        #[allow(dead_code)]
+        #[serde(with = "datetime::seconds")]
+        DateTime<Utc>,
+    );
+    #[derive(Debug, Deserialize)]
+    struct Lenient(
+        #[allow(dead_code)]
+        #[serde(with = "datetime::lenient")]
+        DateTime<Utc>,
+    );
+
+    assert!(serde_json::from_str::<Millis>(&i64::MAX.to_string()).is_err());
+    assert!(serde_json::from_str::<Seconds>(&i64::MIN.to_string()).is_err());
+    assert!(serde_json::from_str::<Lenient>(&u64::MAX.to_string()).is_err());
+    assert!(serde_json::from_str::<Lenient>("1e300").is_err());
+}
+
+#[test]
+fn legacy_helpers() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Legacy {
+        #[serde(
+            serialize_with = "common::util::serialize_datetime",
+            deserialize_with = "common::util::deserialize_datetime"
+        )]
+        date: DateTime<Utc>,
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "common::util::serialize_datetime_opt_opt",
+            deserialize_with = "common::util::deserialize_datetime_opt_opt"
+        )]
+        patch: Option<Option<DateTime<Utc>>>,
+    }
+
+    let value = Legacy {
+        date: date(),
+        patch: Some(None),
+    };
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"date":1680352200123,"patch":null}"#);
+    assert_eq!(serde_json::from_str::<Legacy>(&json).unwrap(), value);
+
+    assert!(serde_json::from_str::<Legacy>(&format!(r#"{{"date":{}}}"#, i64::MAX)).is_err());
+}
@@ -0,0 +1,305 @@
+#![cfg(feature = "backend")]
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::{
+    api::librarian::GetSearchQuery,
+    db::{BookRow, Filter, ListQuery, Pagination, PersonRow, Sort, MIGRATOR},
+    PersonId, Source,
+};
+use sqlx::{Connection, Sqlite, SqliteConnection};
+
+async fn connect() -> SqliteConnection {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    MIGRATOR.run(&mut conn).await.unwrap();
+
+    conn
+}
+
+#[tokio::test]
+async fn book_and_person_rows_convert_to_public() {
+    let mut conn = connect().await;
+
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let source = Source::try_from("openlibrary:OL27448W").unwrap();
+
+    let person_id: i64 = sqlx::query_scalar(
+        "INSERT INTO person (source, name, birth_date, thumb_url, updated_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(&source)
+    .bind("J. R. R. Tolkien")
+    .bind(NaiveDate::from_ymd_opt(1892, 1, 3))
+    .bind(Option::<String>::None)
+    .bind(now)
+    .bind(now)
+    .fetch_one(&mut conn)
+    .await
+    .unwrap();
+
+    sqlx::query("INSERT INTO person_alt (person_id, name) VALUES (?, ?)")
+        .bind(person_id)
+        .bind("John Ronald Reuel Tolkien")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let book_id: i64 = sqlx::query_scalar(
+        "INSERT INTO book (source, title, thumb_url, isbn_10, isbn_13, display_person_id, is_public, language, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(&source)
+    .bind("The Lord of the Rings")
+    .bind("abc123")
+    .bind("0618640150")
+    .bind("9780618640157")
+    .bind(person_id)
+    .bind(true)
+    .bind(1)
+    .bind(now)
+    .bind(now)
+    .fetch_one(&mut conn)
+    .await
+    .unwrap();
+
+    sqlx::query("INSERT INTO book_person (book_id, person_id) VALUES (?, ?)")
+        .bind(book_id)
+        .bind(person_id)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let person: PersonRow = sqlx::query_as("SELECT * FROM person WHERE id = ?")
+        .bind(person_id)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    let other_names: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM person_alt WHERE person_id = ?")
+            .bind(person_id)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+
+    let author = person.into_public(other_names);
+
+    assert_eq!(author.id, person_id as usize);
+    assert_eq!(author.name, "J. R. R. Tolkien");
+    assert_eq!(author.birth_date, NaiveDate::from_ymd_opt(1892, 1, 3));
+    assert_eq!(author.thumb_url, None);
+    assert_eq!(author.other_names, vec!["John Ronald Reuel Tolkien"]);
+    assert_eq!(author.updated_at, now);
+
+    let book: BookRow = sqlx::query_as("SELECT * FROM book WHERE id = ?")
+        .bind(book_id)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(book.source, source);
+
+    let author_ids: Vec<PersonId> =
+        sqlx::query_scalar("SELECT person_id FROM book_person WHERE book_id = ?")
+            .bind(book_id)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+
+    let book = book.into_public(author_ids);
+
+    assert_eq!(book.id, book_id as usize);
+    assert_eq!(book.title.as_deref(), Some("The Lord of the Rings"));
+    assert_eq!(book.thumb_url.as_deref(), Some("abc123"));
+    assert_eq!(
+        book.isbns,
+        Some(vec!["9780618640157".to_string(), "0618640150".to_string()])
+    );
+    assert_eq!(book.display_author_id, Some(person_id as usize));
+    assert_eq!(book.author_ids, vec![person_id as usize]);
+    assert!(book.is_public);
+    assert_eq!(book.language, 1);
+    assert_eq!(book.deleted_at, None);
+}
+
+#[tokio::test]
+async fn deleting_a_person_cascades_to_links() {
+    let mut conn = connect().await;
+
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+
+    let person_id: i64 = sqlx::query_scalar(
+        "INSERT INTO person (source, name, updated_at, created_at) VALUES ('local:1', 'Someone', ?, ?) RETURNING id",
+    )
+    .bind(now)
+    .bind(now)
+    .fetch_one(&mut conn)
+    .await
+    .unwrap();
+
+    sqlx::query("INSERT INTO person_alt (person_id, name) VALUES (?, 'Some One')")
+        .bind(person_id)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    sqlx::query("DELETE FROM person WHERE id = ?")
+        .bind(person_id)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM person_alt")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+}
+
+async fn insert_book(
+    conn: &mut SqliteConnection,
+    title: &str,
+    is_public: bool,
+    isbn: Option<&str>,
+) {
+    let now = Utc::now();
+
+    sqlx::query(
+        "INSERT INTO book (source, title, isbn_13, is_public, created_at, updated_at) VALUES ('local:1', ?, ?, ?, ?, ?)",
+    )
+    .bind(title)
+    .bind(isbn)
+    .bind(is_public)
+    .bind(now)
+    .bind(now)
+    .execute(conn)
+    .await
+    .unwrap();
+}
+
+fn search(query: &str, offset: Option<usize>, limit: Option<usize>) -> GetSearchQuery {
+    GetSearchQuery {
+        query: query.to_string(),
+        offset,
+        limit,
+        view_private: false,
+        with_isbn: false,
+        server_id: String::new(),
+    }
+}
+
+#[test]
+fn list_query_binds_values() {
+    let list = ListQuery::new("book")
+        .filter(Filter::eq("title", "'; DROP TABLE book; --"))
+        .filter(Filter::In("id", vec![1.into(), 2.into()]))
+        .sort(Sort::desc("updated_at"))
+        .paginate(Pagination::new(Some(10), Some(5)));
+
+    assert_eq!(
+        list.select::<Sqlite>().sql(),
+        "SELECT * FROM book WHERE title = ? AND id IN (?, ?) ORDER BY updated_at DESC, id ASC LIMIT ? OFFSET ?"
+    );
+
+    assert_eq!(
+        list.count::<Sqlite>().sql(),
+        "SELECT COUNT(*) FROM book WHERE title = ? AND id IN (?, ?)"
+    );
+}
+
+#[test]
+fn pagination_is_clamped() {
+    assert_eq!(Pagination::new(None, None).limit, 25);
+    assert_eq!(Pagination::new(None, Some(0)).limit, 1);
+    assert_eq!(
+        Pagination::new(Some(3), Some(10_000)),
+        Pagination {
+            offset: 3,
+            limit: 100
+        }
+    );
+}
+
+#[tokio::test]
+async fn book_list_query_filters_and_pages() {
+    let mut conn = connect().await;
+
+    insert_book(&mut conn, "The Hobbit", true, Some("9780547928227")).await;
+    insert_book(&mut conn, "The Silmarillion", true, None).await;
+    insert_book(&mut conn, "Unfinished Tales", false, Some("9780618154043")).await;
+    insert_book(&mut conn, "100% Cotton", true, None).await;
+
+    let list = BookRow::list_query(&search("the", None, Some(1)));
+
+    let total: i64 = list
+        .count()
+        .build_query_scalar()
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    let items: Vec<BookRow> = list
+        .select()
+        .build_query_as()
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    let resp = list.pagination.into_response(total, items);
+
+    assert_eq!(resp.total, 2);
+    assert_eq!(resp.items.len(), 1);
+
+    // Second page returns the other book.
+    let next = BookRow::list_query(&search("the", Some(1), Some(1)));
+
+    let items: Vec<BookRow> = next
+        .select()
+        .build_query_as()
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_ne!(items[0].title, resp.items[0].title);
+
+    // An offset past i64::MAX is past the end rather than negative.
+    let items: Vec<BookRow> = BookRow::list_query(&search("the", Some(usize::MAX), None))
+        .select()
+        .build_query_as()
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    assert!(items.is_empty());
+
+    // Private books and ISBN filter.
+    let mut query = search("", None, None);
+    query.view_private = true;
+    query.with_isbn = true;
+
+    let total: i64 = BookRow::list_query(&query)
+        .count()
+        .build_query_scalar()
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(total, 2);
+
+    // Wildcards are matched literally.
+    let total: i64 = BookRow::list_query(&search("%", None, None))
+        .count()
+        .build_query_scalar()
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(total, 1);
+}
@@ -0,0 +1,135 @@
+use common::{
+    api::{ApiErrorResponse, ErrorCodeResponse, WrappingResponse},
+    error::{Error, ResultExt},
+    validate_isbn, BookId, ImageIdType, ImageType, Source,
+};
+use serde_json::json;
+
+#[test]
+fn codes_and_public_descriptions() {
+    let error = Error::InvalidIsbn(String::from("123"));
+
+    assert_eq!(error.code(), ErrorCodeResponse::InvalidIsbn);
+    assert_eq!(error.public_description(), "Invalid ISBN");
+    assert!(!error.is_internal());
+
+    let error = Error::Io(std::io::Error::other("/secret/path"));
+
+    assert!(error.is_internal());
+
+    let response = error.to_api_response();
+
+    assert_eq!(response.code, ErrorCodeResponse::Internal);
+    assert!(!response.description.contains("secret"));
+}
+
+#[test]
+fn into_responses() {
+    let response: ApiErrorResponse = Error::SourceSplit.into();
+
+    assert_eq!(response.code, ErrorCodeResponse::InvalidSource);
+
+    let response: WrappingResponse<()> = Error::InvalidId(String::from("a")).into();
+
+    assert!(matches!(
+        response,
+        WrappingResponse::Error(ApiErrorResponse {
+            code: ErrorCodeResponse::InvalidId,
+            ..
+        })
+    ));
+
+    // Internal details are left out.
+    assert_eq!(
+        serde_json::to_value(Error::InvalidImage(String::from("9-9"))).unwrap(),
+        serde_json::to_value(ApiErrorResponse::new_with_code(
+            "Invalid Image",
+            ErrorCodeResponse::InvalidImage
+        ))
+        .unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(Error::Poisoned).unwrap()["description"],
+        json!("Internal Server Error")
+    );
+}
+
+#[test]
+fn specific_types_return_errors() {
+    assert_eq!(
+        "12-1".parse::<ImageIdType>().unwrap(),
+        ImageIdType {
+            id: 12,
+            type_of: ImageType::Person
+        }
+    );
+    assert_eq!(ImageIdType::new_book(BookId::from(3)).to_string(), "3-0");
+
+    assert!(matches!(
+        "12".parse::<ImageIdType>(),
+        Err(Error::InvalidImage(_))
+    ));
+    assert!(matches!(
+        "a-1".parse::<ImageIdType>(),
+        Err(Error::InvalidId(_))
+    ));
+    assert!(matches!(
+        "12-9".parse::<ImageIdType>(),
+        Err(Error::InvalidImage(_))
+    ));
+
+    assert_eq!(ImageType::try_from(1_i64).unwrap(), ImageType::Person);
+    assert!(ImageType::try_from(-1_i64).is_err());
+    assert!(ImageType::try_from(300_i64).is_err());
+
+    assert!(matches!(
+        Source::try_from(String::from("no-colon")),
+        Err(Error::SourceSplit)
+    ));
+    assert_eq!(
+        Source::try_from("google:abc").unwrap().to_string(),
+        "google:abc"
+    );
+
+    assert_eq!(validate_isbn("0-261-10334-2").unwrap(), "9780261103344");
+    assert!(matches!(validate_isbn("123"), Err(Error::InvalidIsbn(_))));
+}
+
+fn read_config() -> common::error::Result<String> {
+    std::fs::read_to_string("/missing/config.json").context("reading config")
+}
+
+#[test]
+fn context_chain() {
+    let error = read_config()
+        .with_context(|| format!("starting server {}", 1))
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "starting server 1");
+    assert!(matches!(error.root(), Error::Io(_)));
+    // Codes come from the root error.
+    assert_eq!(error.code(), ErrorCodeResponse::Internal);
+
+    let chain = error.display_chain();
+    let lines = chain.lines().collect::<Vec<_>>();
+
+    assert!(lines[0].starts_with("starting server 1 (tests/error.rs:"));
+    assert_eq!(lines[2], "Caused by:");
+    assert!(lines[3].starts_with("    0: reading config (tests/error.rs:"));
+    assert!(lines[4].starts_with("    1: IO Error: "));
+    assert_eq!(lines.len(), 5);
+}
+
+#[test]
+fn chain_without_context() {
+    assert_eq!(
+        Error::SourceSplit.display_chain(),
+        "Missing ':' from Source"
+    );
+    assert!(matches!(Error::SourceSplit.root(), Error::SourceSplit));
+
+    let error = Error::InvalidId(String::from("a")).context("parsing id");
+
+    assert_eq!(error.public_description(), "Invalid Id");
+    assert_eq!(error.to_api_response().code, ErrorCodeResponse::InvalidId);
+}
@@ -0,0 +1,154 @@
+use common::{
+    isbn_10_to_13, match_book, normalize_isbn, parse_book_id, parse_isbn_10, parse_isbn_13,
+    rank_book_matches, title_similarity, BookMatchFields, IdType,
+};
+
+fn fields(title: &str, isbns: &[&str], authors: &[&str], year: Option<i32>) -> BookMatchFields {
+    BookMatchFields {
+        title: Some(title.to_string()),
+        isbns: isbns.iter().map(|v| v.to_string()).collect(),
+        authors: authors.iter().map(|v| v.to_string()).collect(),
+        year,
+    }
+}
+
+#[test]
+fn isbn_10() {
+    assert_eq!(parse_isbn_10("0261103342").as_deref(), Some("0261103342"));
+    assert_eq!(
+        parse_isbn_10("0-261-10334-2").as_deref(),
+        Some("0261103342")
+    );
+    assert_eq!(parse_isbn_10("080442957x").as_deref(), Some("080442957X"));
+
+    // Wrong check digit.
+    assert_eq!(parse_isbn_10("0261103343"), None);
+    // 'X' is only allowed as the check digit.
+    assert_eq!(parse_isbn_10("X261103342"), None);
+    assert_eq!(parse_isbn_10("02611033420"), None);
+    assert_eq!(parse_isbn_10("0000000000"), None);
+    assert_eq!(parse_isbn_10(""), None);
+}
+
+#[test]
+fn isbn_13() {
+    assert_eq!(
+        parse_isbn_13("9780261103344").as_deref(),
+        Some("9780261103344")
+    );
+    assert_eq!(
+        parse_isbn_13("978-0-261-10334-4").as_deref(),
+        Some("9780261103344")
+    );
+
+    assert_eq!(parse_isbn_13("9780261103345"), None);
+    assert_eq!(parse_isbn_13("97802611033440"), None);
+    assert_eq!(parse_isbn_13("978026110334X"), None);
+    assert_eq!(parse_isbn_13("0000000000000"), None);
+}
+
+#[test]
+fn isbn_10_and_13_are_the_same() {
+    assert_eq!(
+        isbn_10_to_13("0261103342").as_deref(),
+        Some("9780261103344")
+    );
+    assert_eq!(
+        isbn_10_to_13("080442957X").as_deref(),
+        Some("9780804429573")
+    );
+    assert_eq!(isbn_10_to_13("0261103343"), None);
+
+    assert_eq!(
+        normalize_isbn("0-261-10334-2"),
+        normalize_isbn("9780261103344")
+    );
+}
+
+#[test]
+fn book_ids() {
+    assert!(matches!(
+        parse_book_id("urn:isbn:9780261103344"),
+        IdType::Isbn(v) if v == "9780261103344"
+    ));
+    assert!(matches!(
+        parse_book_id("amazon:B00ABC"),
+        IdType::UnknownKeyValue(k, v) if k == "amazon" && v == "B00ABC"
+    ));
+    assert_eq!(
+        parse_book_id("0261103342").as_isbn_13_or_10().as_deref(),
+        Some("0261103342")
+    );
+}
+
+#[test]
+fn shared_isbn_is_confident() {
+    let local = fields(
+        "The Hobbit",
+        &["0261103342"],
+        &["J. R. R. Tolkien"],
+        Some(1937),
+    );
+    let candidate = fields("Hobbit", &["9780261103344"], &[], None);
+
+    let value = match_book(&local, &candidate);
+
+    assert_eq!(value.breakdown.isbn, Some(1.0));
+    assert!(value.is_confident());
+}
+
+#[test]
+fn matches_without_isbns() {
+    let local = fields(
+        "The Way of Kings (The Stormlight Archive, #1)",
+        &[],
+        &["Brandon Sanderson"],
+        Some(2010),
+    );
+
+    let same = fields("The Way of Kings", &[], &["Sanderson, Brandon"], Some(2010));
+    let other = fields("Words of Radiance", &[], &["Brandon Sanderson"], Some(2014));
+
+    assert!(match_book(&local, &same).is_confident());
+    assert!(!match_book(&local, &other).is_confident());
+
+    let ranked = rank_book_matches(&local, &[other, same]);
+
+    assert_eq!(ranked[0].0, 1);
+    assert!(ranked[0].1.confidence > ranked[1].1.confidence);
+}
+
+#[test]
+fn title_only_is_not_confident() {
+    let local = fields("Poems", &[], &[], None);
+    let candidate = fields("Poems", &[], &[], None);
+
+    let value = match_book(&local, &candidate);
+
+    assert_eq!(value.breakdown.title, Some(1.0));
+    assert!(!value.is_confident());
+
+    // Even when both have ISBNs which don't match.
+    let local = fields("Poems", &["9780441172719"], &[], None);
+    let candidate = fields("Poems", &["9780340960196"], &[], None);
+
+    assert!(!match_book(&local, &candidate).is_confident());
+}
+
+#[test]
+fn different_isbns_lower_the_score() {
+    let local = fields("Dune", &["9780441172719"], &["Frank Herbert"], Some(1965));
+    let candidate = fields("Dune", &["9780340960196"], &["Frank Herbert"], Some(1965));
+
+    let value = match_book(&local, &candidate);
+
+    assert_eq!(value.breakdown.isbn, Some(0.0));
+    assert!(value.confidence < 0.9);
+}
+
+#[test]
+fn titles() {
+    assert_eq!(title_similarity("The Hobbit", "the hobbit"), 1.0);
+    assert_eq!(title_similarity("Hobbit, The", "The Hobbit"), 1.0);
+    assert!(title_similarity("Dune", "Emma") < 0.5);
+}
@@ -0,0 +1,111 @@
+use common::{name_similarity, PersonName};
+
+fn parse(value: &str) -> (String, String, Option<String>) {
+    let name = PersonName::parse(value);
+
+    (name.given_name(), name.family, name.suffix)
+}
+
+#[test]
+fn given_family_order() {
+    let name = PersonName::parse("Tolkien, J.R.R.");
+
+    assert_eq!(name.display_name(), "J. R. R. Tolkien");
+    assert_eq!(name.sort_name(), "Tolkien, J. R. R.");
+    assert_eq!(PersonName::parse("J. R. R. Tolkien"), name);
+
+    assert_eq!(
+        parse("Ludwig van Beethoven"),
+        (String::from("Ludwig"), String::from("van Beethoven"), None)
+    );
+    assert_eq!(parse("Plato"), (String::new(), String::from("Plato"), None));
+}
+
+#[test]
+fn suffixes() {
+    let name = PersonName::parse("Martin Luther King, Jr.");
+
+    assert_eq!(name.family, "King");
+    assert_eq!(name.suffix.as_deref(), Some("Jr."));
+    assert_eq!(name.sort_name(), "King, Martin Luther, Jr.");
+    assert_eq!(name.display_name(), "Martin Luther King Jr.");
+
+    // Every trailing suffix is kept in order.
+    let name = PersonName::parse("John Smith Jr. PhD");
+
+    assert_eq!(name.given_name(), "John");
+    assert_eq!(name.family, "Smith");
+    assert_eq!(name.suffix.as_deref(), Some("Jr. PhD"));
+
+    assert_eq!(
+        PersonName::parse("John Smith Jr., PhD").suffix.as_deref(),
+        Some("Jr. PhD")
+    );
+
+    assert_eq!(
+        parse("John Smith III"),
+        (
+            String::from("John"),
+            String::from("Smith"),
+            Some(String::from("III"))
+        )
+    );
+    assert_eq!(
+        parse("Smith, John, iv"),
+        (
+            String::from("John"),
+            String::from("Smith"),
+            Some(String::from("IV"))
+        )
+    );
+    assert_eq!(
+        parse("Smith, John II"),
+        (
+            String::from("John"),
+            String::from("Smith"),
+            Some(String::from("II"))
+        )
+    );
+}
+
+#[test]
+fn numerals_which_are_names() {
+    assert_eq!(
+        parse("Schwab, V."),
+        (String::from("V."), String::from("Schwab"), None)
+    );
+    assert_eq!(
+        parse("Smith, Ii"),
+        (String::from("Ii"), String::from("Smith"), None)
+    );
+    assert_eq!(
+        parse("V. E. Schwab"),
+        (String::from("V. E."), String::from("Schwab"), None)
+    );
+}
+
+#[test]
+fn fixes_single_case() {
+    assert_eq!(
+        PersonName::parse("JEAN-PAUL SARTRE").display_name(),
+        "Jean-Paul Sartre"
+    );
+    assert_eq!(
+        PersonName::parse("o'brien, patrick").display_name(),
+        "Patrick O'Brien"
+    );
+    assert_eq!(
+        PersonName::parse("LUDWIG VAN BEETHOVEN").display_name(),
+        "Ludwig van Beethoven"
+    );
+}
+
+#[test]
+fn similar_names() {
+    assert!(PersonName::parse("J. R. R. Tolkien")
+        .is_likely_same_person(&PersonName::parse("Tolkien, John Ronald Reuel")));
+    assert!(name_similarity("Haruki Murakami", "Murakami Haruki") >= 0.85);
+    assert!(name_similarity("John Smith", "John A. Smith") >= 0.85);
+    assert!(name_similarity("Stephen King", "Stephen Fry") < 0.85);
+    assert_eq!(name_similarity("Ursula Le Guin", "Ursula Le Guin"), 1.0);
+}
@@ -0,0 +1,293 @@
+use chrono::{TimeZone, Utc};
+use common::{
+    api::{
+        librarian::{PartialBook, PublicAuthor},
+        opds::{OpdsBook, OpdsFeed, OpdsLink, OpdsPagination, OpdsUrls, REL_ACQUISITION},
+        QueryListResponse,
+    },
+    ThumbnailStore,
+};
+
+struct Urls;
+
+impl OpdsUrls for Urls {
+    fn book(&self, id: usize) -> String {
+        format!("/book/{id}")
+    }
+
+    fn author_books(&self, id: usize) -> String {
+        format!("/opds/author/{id}")
+    }
+
+    fn thumbnail(&self, value: &ThumbnailStore) -> Option<String> {
+        value.as_value().map(|v| format!("/image/{v}"))
+    }
+
+    fn acquisitions(&self, book_id: usize) -> Vec<OpdsLink> {
+        vec![
+            OpdsLink::new(REL_ACQUISITION, format!("/book/{book_id}/download"))
+                .with_type("application/epub+zip"),
+        ]
+    }
+}
+
+fn feed() -> OpdsFeed {
+    let mut feed = OpdsFeed::new(
+        "urn:uuid:library",
+        "Books",
+        "/opds/books?sort=title",
+        Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+    );
+
+    feed.start_url = Some(String::from("/opds"));
+
+    feed
+}
+
+fn books() -> QueryListResponse<PartialBook> {
+    QueryListResponse {
+        offset: 20,
+        limit: 10,
+        total: 45,
+        items: vec![
+            PartialBook {
+                id: 1,
+                title: Some(String::from("Pride & Prejudice")),
+                description: Some(String::from("<b>Bold</b>")),
+                rating: 0.0,
+                thumb_url: Some(String::from("abc")),
+                isbns: Some(vec![String::from("9780141439518")]),
+                is_public: true,
+                available_at: None,
+                language: 0,
+            },
+            PartialBook {
+                id: 2,
+                title: None,
+                description: None,
+                rating: 0.0,
+                thumb_url: None,
+                isbns: None,
+                is_public: true,
+                available_at: None,
+                language: 1,
+            },
+        ],
+    }
+}
+
+fn author() -> PublicAuthor {
+    PublicAuthor {
+        id: 7,
+        name: String::from("Jane Austen"),
+        description: None,
+        birth_date: None,
+        thumb_url: None,
+        other_names: Vec::new(),
+        updated_at: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+        created_at: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+        deleted_at: None,
+    }
+}
+
+#[test]
+fn pagination_offsets() {
+    let page = OpdsPagination::from(&books());
+
+    assert_eq!(page.previous_offset(), Some(10));
+    assert_eq!(page.next_offset(), Some(30));
+    assert_eq!(page.last_offset(), 40);
+    assert_eq!(page.current_page(), 3);
+
+    let last = OpdsPagination {
+        offset: 40,
+        limit: 10,
+        total: 45,
+    };
+
+    assert_eq!(last.next_offset(), None);
+}
+
+#[test]
+fn acquisition_xml_is_valid_atom() {
+    let list = books();
+    let authors = vec![author()];
+
+    let entries = list
+        .items
+        .iter()
+        .map(|v| OpdsBook::from(v).with_authors(&[7], &authors))
+        .collect::<Vec<_>>();
+
+    let xml = feed().acquisition_xml(Some(OpdsPagination::from(&list)), &entries, &Urls);
+
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    let root = doc.root_element();
+
+    assert_eq!(root.tag_name().name(), "feed");
+
+    let link = |rel: &str| {
+        root.children()
+            .find(|v| v.tag_name().name() == "link" && v.attribute("rel") == Some(rel))
+            .and_then(|v| v.attribute("href"))
+    };
+
+    assert_eq!(
+        link("next"),
+        Some("/opds/books?sort=title&offset=30&limit=10")
+    );
+    assert_eq!(
+        link("previous"),
+        Some("/opds/books?sort=title&offset=10&limit=10")
+    );
+    assert_eq!(
+        link("last"),
+        Some("/opds/books?sort=title&offset=40&limit=10")
+    );
+    assert_eq!(link("start"), Some("/opds"));
+
+    let entries = root
+        .children()
+        .filter(|v| v.tag_name().name() == "entry")
+        .collect::<Vec<_>>();
+
+    assert_eq!(entries.len(), 2);
+
+    let text = |node: roxmltree::Node, name: &str| {
+        node.descendants()
+            .find(|v| v.tag_name().name() == name)
+            .and_then(|v| v.text())
+            .map(str::to_string)
+    };
+
+    assert_eq!(
+        text(entries[0], "title").as_deref(),
+        Some("Pride & Prejudice")
+    );
+    assert_eq!(
+        text(entries[0], "id").as_deref(),
+        Some("urn:isbn:9780141439518")
+    );
+    assert_eq!(text(entries[0], "summary").as_deref(), Some("<b>Bold</b>"));
+    assert_eq!(text(entries[0], "name").as_deref(), Some("Jane Austen"));
+    assert_eq!(
+        text(entries[0], "updated").as_deref(),
+        Some("2024-01-02T03:04:05Z")
+    );
+
+    assert!(entries[0].children().any(|v| {
+        v.attribute("rel") == Some("http://opds-spec.org/image/thumbnail")
+            && v.attribute("href") == Some("/image/abc")
+    }));
+
+    assert_eq!(text(entries[1], "id").as_deref(), Some("/book/2"));
+    assert_eq!(text(entries[1], "language").as_deref(), Some("fr"));
+    assert!(!entries[1]
+        .children()
+        .any(|v| v.attribute("rel") == Some("http://opds-spec.org/image")));
+}
+
+#[test]
+fn navigation_xml_links_authors() {
+    let xml = feed().navigation_xml(None, &[author()], &Urls);
+
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+
+    let entry = doc
+        .descendants()
+        .find(|v| v.tag_name().name() == "entry")
+        .unwrap();
+
+    let link = entry
+        .children()
+        .find(|v| v.attribute("rel") == Some("subsection"))
+        .unwrap();
+
+    assert_eq!(link.attribute("href"), Some("/opds/author/7"));
+    assert!(link.attribute("type").unwrap().contains("kind=acquisition"));
+
+    // No pagination links without pagination.
+    assert!(!doc
+        .descendants()
+        .any(|v| v.attribute("rel") == Some("next")));
+}
+
+#[test]
+fn opds_2_json() {
+    let list = books();
+    let entries = list.items.iter().map(OpdsBook::from).collect::<Vec<_>>();
+
+    let value = feed().acquisition_json(Some(OpdsPagination::from(&list)), &entries, &Urls);
+
+    assert_eq!(value["metadata"]["numberOfItems"], 45);
+    assert_eq!(value["metadata"]["currentPage"], 3);
+    assert_eq!(value["links"][0]["rel"], "self");
+    assert_eq!(value["links"][1]["type"], "application/opds+json");
+
+    let publication = &value["publications"][0];
+
+    assert_eq!(publication["metadata"]["title"], "Pride & Prejudice");
+    assert_eq!(publication["metadata"]["language"], "en");
+    assert_eq!(publication["images"][0]["href"], "/image/abc");
+    assert_eq!(publication["links"][1]["type"], "application/epub+zip");
+
+    let value = feed().navigation_json(None, &[author()], &Urls);
+
+    assert_eq!(value["navigation"][0]["href"], "/opds/author/7");
+    assert_eq!(value["navigation"][0]["title"], "Jane Austen");
+}
+
+/// Only book 1 can be downloaded.
+struct PartialUrls;
+
+impl OpdsUrls for PartialUrls {
+    fn book(&self, id: usize) -> String {
+        Urls.book(id)
+    }
+
+    fn author_books(&self, id: usize) -> String {
+        Urls.author_books(id)
+    }
+
+    fn thumbnail(&self, value: &ThumbnailStore) -> Option<String> {
+        Urls.thumbnail(value)
+    }
+
+    fn acquisitions(&self, book_id: usize) -> Vec<OpdsLink> {
+        if book_id == 1 {
+            Urls.acquisitions(book_id)
+        } else {
+            vec![OpdsLink::new("related", format!("/book/{book_id}/related"))]
+        }
+    }
+}
+
+#[test]
+fn books_without_acquisitions_are_skipped() {
+    let list = books();
+    let entries = list.items.iter().map(OpdsBook::from).collect::<Vec<_>>();
+
+    let xml = feed().acquisition_xml(None, &entries, &PartialUrls);
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+
+    let entries_xml = doc
+        .descendants()
+        .filter(|v| v.has_tag_name("entry"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(entries_xml.len(), 1);
+    assert!(entries_xml[0]
+        .children()
+        .any(|v| v.attribute("rel") == Some(REL_ACQUISITION)));
+
+    let value = feed().acquisition_json(None, &entries, &PartialUrls);
+
+    assert_eq!(value["publications"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        value["publications"][0]["metadata"]["title"],
+        "Pride & Prejudice"
+    );
+
+    assert!(OpdsLink::new("http://opds-spec.org/acquisition/open-access", "/").is_acquisition());
+    assert!(!OpdsLink::new("http://opds-spec.org/acquisitions", "/").is_acquisition());
+}
@@ -0,0 +1,160 @@
+use chrono::NaiveDate;
+use common::{parse_opf_date, ContributorRole, IdType, Language, OpfMetadata, OpfSeries};
+
+static EPUB_2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="2.0" unique-identifier="uuid_id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+    <dc:title>The Fellowship of the Ring (Kindle Edition)</dc:title>
+    <dc:creator opf:role="aut" opf:file-as="Tolkien, J. R. R.">J. R. R. Tolkien</dc:creator>
+    <dc:contributor opf:role="ill">Alan Lee</dc:contributor>
+    <dc:contributor opf:role="bkp">calibre (5.0.0) [https://calibre-ebook.com]</dc:contributor>
+    <dc:description>&lt;p&gt;The first part of &lt;i&gt;The Lord of the Rings&lt;/i&gt;.&lt;/p&gt;</dc:description>
+    <dc:publisher>Houghton Mifflin</dc:publisher>
+    <dc:identifier opf:scheme="uuid" id="uuid_id">a2e4e2a2-1f4c-4c8d-9d7e-16f41ff1c6e3</dc:identifier>
+    <dc:identifier opf:scheme="ISBN">0-618-57494-8</dc:identifier>
+    <dc:identifier>urn:isbn:9780618574940</dc:identifier>
+    <dc:identifier opf:scheme="GOODREADS">34</dc:identifier>
+    <dc:date opf:event="modification">2020-01-01</dc:date>
+    <dc:date opf:event="publication">1954-07-29T00:00:00+00:00</dc:date>
+    <dc:language>eng</dc:language>
+    <meta name="calibre:series" content="The Lord of the Rings"/>
+    <meta name="calibre:series_index" content="1.0"/>
+  </metadata>
+</package>"#;
+
+static EPUB_3: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="pub-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title id="t2">A Subtitle</dc:title>
+    <meta refines="#t2" property="title-type">subtitle</meta>
+    <dc:title id="t1">Les Misérables</dc:title>
+    <meta refines="#t1" property="title-type">main</meta>
+    <dc:creator id="c1">Victor Hugo</dc:creator>
+    <meta refines="#c1" property="role" scheme="marc:relators">aut</meta>
+    <meta refines="#c1" property="file-as">Hugo, Victor</meta>
+    <dc:creator id="c2">Isabel F. Hapgood</dc:creator>
+    <meta refines="#c2" property="role" scheme="marc:relators">trl</meta>
+    <dc:identifier id="pub-id">urn:uuid:1d7f3a4e-0000-4000-8000-000000000000</dc:identifier>
+    <dc:date>1862-04</dc:date>
+    <dc:language>fr-FR</dc:language>
+    <meta property="belongs-to-collection" id="col">Classics</meta>
+    <meta refines="#col" property="collection-type">series</meta>
+    <meta refines="#col" property="group-position">12</meta>
+  </metadata>
+</package>"##;
+
+/// Written by Calibre for a book without a publication date.
+static CALIBRE_NO_DATE: &str = r#"<?xml version='1.0' encoding='utf-8'?>
+<package xmlns="http://www.idpf.org/2007/opf" unique-identifier="uuid_id" version="2.0">
+    <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+        <dc:identifier opf:scheme="calibre" id="calibre_id">42</dc:identifier>
+        <dc:identifier opf:scheme="uuid" id="uuid_id">0e5f1c3a-8b6e-4f0e-9c44-6f1c2b8d7e11</dc:identifier>
+        <dc:title>Notes</dc:title>
+        <dc:creator opf:file-as="Unknown" opf:role="aut">Unknown</dc:creator>
+        <dc:contributor opf:file-as="calibre" opf:role="bkp">calibre (6.29.0) [https://calibre-ebook.com]</dc:contributor>
+        <dc:date>0101-01-01T00:00:00+00:00</dc:date>
+        <dc:language>und</dc:language>
+        <meta name="calibre:timestamp" content="2023-11-02T10:12:44.123456+00:00"/>
+    </metadata>
+    <guide/>
+</package>"#;
+
+#[test]
+fn parses_epub_2_metadata() {
+    let opf = OpfMetadata::parse(EPUB_2).unwrap();
+
+    assert_eq!(
+        opf.title.as_deref(),
+        Some("The Fellowship of the Ring (Kindle Edition)")
+    );
+    assert_eq!(
+        opf.clean_title.as_deref(),
+        Some("The Fellowship of the Ring")
+    );
+    assert_eq!(
+        opf.description.as_deref(),
+        Some("The first part of The Lord of the Rings.")
+    );
+    assert_eq!(opf.publisher.as_deref(), Some("Houghton Mifflin"));
+
+    assert_eq!(opf.isbns, vec!["9780618574940", "0618574948"]);
+    assert_eq!(opf.identifiers.len(), 4);
+    assert_eq!(
+        opf.identifiers[0],
+        IdType::Uuid(String::from("a2e4e2a2-1f4c-4c8d-9d7e-16f41ff1c6e3"))
+    );
+    assert_eq!(
+        opf.identifiers[3],
+        IdType::UnknownKeyValue(String::from("goodreads"), String::from("34"))
+    );
+
+    let authors = opf.authors().collect::<Vec<_>>();
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[0].name, "J. R. R. Tolkien");
+    assert_eq!(authors[0].file_as.as_deref(), Some("Tolkien, J. R. R."));
+
+    assert_eq!(opf.contributors[1].role, ContributorRole::Illustrator);
+    assert_eq!(opf.contributors[2].role.code(), "bkp");
+
+    assert_eq!(opf.available_at, NaiveDate::from_ymd_opt(1954, 7, 29));
+    assert_eq!(opf.language, Some(Language::from_name("english")));
+    assert_eq!(
+        opf.series,
+        Some(OpfSeries {
+            name: String::from("The Lord of the Rings"),
+            index: Some(1.0),
+        })
+    );
+}
+
+#[test]
+fn parses_epub_3_refines() {
+    let opf = OpfMetadata::parse(EPUB_3).unwrap();
+
+    assert_eq!(opf.title.as_deref(), Some("Les Misérables"));
+    assert!(opf.isbns.is_empty());
+
+    assert_eq!(opf.contributors.len(), 2);
+    assert_eq!(opf.contributors[0].role, ContributorRole::Author);
+    assert_eq!(opf.contributors[0].file_as.as_deref(), Some("Hugo, Victor"));
+    assert_eq!(opf.contributors[1].role, ContributorRole::Translator);
+
+    assert_eq!(opf.available_at, NaiveDate::from_ymd_opt(1862, 4, 1));
+    assert_eq!(opf.language, Some(Language::from_name("french")));
+    assert_eq!(
+        opf.series,
+        Some(OpfSeries {
+            name: String::from("Classics"),
+            index: Some(12.0),
+        })
+    );
+
+    let book = opf.to_public_book(chrono::Utc::now());
+
+    assert_eq!(book.isbns, None);
+    assert_eq!(book.language, 1);
+    assert!(!book.is_public);
+}
+
+#[test]
+fn rejects_invalid_documents() {
+    assert!(OpfMetadata::parse("<package><metadata>").is_err());
+    assert!(OpfMetadata::parse("<package/>").is_err());
+
+    let opf = OpfMetadata::parse("<package><metadata/></package>").unwrap();
+    assert_eq!(opf.title, None);
+    assert_eq!(opf.language, None);
+}
+
+#[test]
+fn ignores_calibre_placeholder_date() {
+    let opf = OpfMetadata::parse(CALIBRE_NO_DATE).unwrap();
+
+    assert_eq!(opf.title.as_deref(), Some("Notes"));
+    assert_eq!(opf.available_at, None);
+
+    assert_eq!(parse_opf_date("0101-01-01T00:00:00+00:00"), None);
+    assert_eq!(parse_opf_date("0101-01-01"), None);
+    assert_eq!(parse_opf_date("0101"), None);
+    assert_eq!(parse_opf_date("1001"), NaiveDate::from_ymd_opt(1001, 1, 1));
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::{
+    api::librarian::{AuthorPatch, BookPatch, PublicAuthor},
+    util::{apply_required, patch_from_field_map, ApplyPatch, Patch},
+};
+use serde_json::json;
+
+fn author() -> PublicAuthor {
+    PublicAuthor {
+        id: 1,
+        name: String::from("Ursula K. Le Guin"),
+        description: Some(String::from("Author of Earthsea.")),
+        birth_date: NaiveDate::from_ymd_opt(1929, 10, 21),
+        thumb_url: None,
+        other_names: Vec::new(),
+        updated_at: Utc.timestamp_millis_opt(0).unwrap(),
+        created_at: Utc.timestamp_millis_opt(0).unwrap(),
+        deleted_at: None,
+    }
+}
+
+#[test]
+fn missing_null_and_value() {
+    let patch: BookPatch = serde_json::from_value(json!({
+        "title": "Dune",
+        "description": null,
+    }))
+    .unwrap();
+
+    assert_eq!(patch.title, Patch::Value(String::from("Dune")));
+    assert_eq!(patch.description, Patch::Null);
+    assert_eq!(patch.publisher, Patch::Missing);
+    assert_eq!(patch.rating, None);
+
+    // Missing fields aren't written back out.
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        json!({
+            "title": "Dune",
+            "description": null,
+        })
+    );
+}
+
+#[test]
+fn apply_to_option() {
+    let mut value = Some(1);
+
+    assert!(!Patch::Missing.apply(&mut value));
+    assert_eq!(value, Some(1));
+
+    assert!(!Patch::Value(1).apply(&mut value));
+    assert!(Patch::Value(2).apply(&mut value));
+    assert_eq!(value, Some(2));
+
+    assert!(Patch::<i32>::Null.apply(&mut value));
+    assert_eq!(value, None);
+    assert!(!Patch::<i32>::Null.apply(&mut value));
+
+    let mut value = 5;
+
+    assert!(!apply_required(None, &mut value));
+    assert!(!apply_required(Some(5), &mut value));
+    assert!(apply_required(Some(6), &mut value));
+    assert_eq!(value, 6);
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Patch::<i32>::from(None::<Option<i32>>), Patch::Missing);
+    assert_eq!(Patch::<i32>::from(Some(None::<i32>)), Patch::Null);
+    assert_eq!(Patch::<i32>::from(Some(Some(3))), Patch::Value(3));
+    assert_eq!(Patch::from(None::<i32>), Patch::Null);
+
+    assert_eq!(Patch::Value(3).map(|v| v * 2).value(), Some(6));
+    assert_eq!(Patch::<i32>::Null.into_option(), Some(None));
+    assert_eq!(Patch::<i32>::Missing.into_option(), None);
+}
+
+#[test]
+fn apply_author_patch() {
+    let mut value = author();
+
+    assert!(!value.apply_patch(AuthorPatch::default()));
+
+    let changed = value.apply_patch(AuthorPatch {
+        description: Patch::Null,
+        birth_date: Patch::Missing,
+        other_names: Some(vec![String::from("Ursula Kroeber")]),
+        ..AuthorPatch::default()
+    });
+
+    assert!(changed);
+    assert_eq!(value.name, "Ursula K. Le Guin");
+    assert_eq!(value.description, None);
+    assert_eq!(value.birth_date, NaiveDate::from_ymd_opt(1929, 10, 21));
+    assert_eq!(value.other_names, vec![String::from("Ursula Kroeber")]);
+}
+
+#[test]
+fn patch_from_map() {
+    let mut map = HashMap::new();
+    map.insert("birth_date", json!(null));
+    map.insert("name", json!("Le Guin"));
+
+    let patch: AuthorPatch = patch_from_field_map(map).unwrap();
+
+    assert_eq!(patch.name.as_deref(), Some("Le Guin"));
+    assert_eq!(patch.birth_date, Patch::Null);
+    assert_eq!(patch.thumb_url, Patch::Missing);
+}
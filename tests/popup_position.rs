@@ -0,0 +1,130 @@
+#![cfg(feature = "frontend")]
+
+use common::component::popup::{
+    button::ButtonPopupPosition,
+    position::{place_around, place_at_point, Rect, Size, VIEWPORT_MARGIN},
+};
+
+const VIEWPORT: Size = Size {
+    width: 1000.0,
+    height: 800.0,
+};
+
+const POPUP: Size = Size {
+    width: 200.0,
+    height: 300.0,
+};
+
+#[test]
+fn at_point_stays_put_when_it_fits() {
+    assert_eq!(
+        place_at_point(100.0, 100.0, POPUP, VIEWPORT),
+        (100.0, 100.0)
+    );
+}
+
+#[test]
+fn at_point_flips_near_the_edges() {
+    // Bottom right corner opens up and to the left.
+    assert_eq!(
+        place_at_point(950.0, 700.0, POPUP, VIEWPORT),
+        (750.0, 400.0)
+    );
+}
+
+#[test]
+fn at_point_shifts_when_it_cant_flip() {
+    let tall = Size {
+        width: 200.0,
+        height: 700.0,
+    };
+
+    let (_, top) = place_at_point(100.0, 300.0, tall, VIEWPORT);
+
+    assert_eq!(top, VIEWPORT.height - VIEWPORT_MARGIN - tall.height);
+
+    // Larger than the viewport sticks to the start.
+    let huge = Size {
+        width: 2000.0,
+        height: 2000.0,
+    };
+
+    assert_eq!(
+        place_at_point(500.0, 500.0, huge, VIEWPORT),
+        (VIEWPORT_MARGIN, VIEWPORT_MARGIN)
+    );
+}
+
+#[test]
+fn around_uses_preferred_side() {
+    let anchor = Rect {
+        left: 100.0,
+        top: 100.0,
+        width: 50.0,
+        height: 20.0,
+    };
+
+    let placement = place_around(anchor, POPUP, VIEWPORT, ButtonPopupPosition::Bottom);
+
+    assert_eq!(placement.position, ButtonPopupPosition::Bottom);
+    assert_eq!((placement.left, placement.top), (100.0, 120.0));
+}
+
+#[test]
+fn around_flips_to_the_opposite_side() {
+    let anchor = Rect {
+        left: 100.0,
+        top: 700.0,
+        width: 50.0,
+        height: 20.0,
+    };
+
+    let placement = place_around(anchor, POPUP, VIEWPORT, ButtonPopupPosition::Bottom);
+
+    assert_eq!(placement.position, ButtonPopupPosition::Top);
+    assert_eq!((placement.left, placement.top), (100.0, 400.0));
+}
+
+#[test]
+fn around_shifts_along_the_edge() {
+    // Button in the top right corner.
+    let anchor = Rect {
+        left: 960.0,
+        top: 10.0,
+        width: 30.0,
+        height: 20.0,
+    };
+
+    let placement = place_around(anchor, POPUP, VIEWPORT, ButtonPopupPosition::Bottom);
+
+    assert_eq!(placement.position, ButtonPopupPosition::Bottom);
+    assert_eq!(
+        placement.left,
+        VIEWPORT.width - VIEWPORT_MARGIN - POPUP.width
+    );
+
+    // Right doesn't fit so it goes left.
+    let placement = place_around(anchor, POPUP, VIEWPORT, ButtonPopupPosition::Right);
+
+    assert_eq!(placement.position, ButtonPopupPosition::Left);
+    assert_eq!(placement.left, 760.0);
+}
+
+#[test]
+fn around_picks_the_most_room_when_nothing_fits() {
+    let huge = Size {
+        width: 900.0,
+        height: 780.0,
+    };
+
+    let anchor = Rect {
+        left: 500.0,
+        top: 600.0,
+        width: 30.0,
+        height: 20.0,
+    };
+
+    let placement = place_around(anchor, huge, VIEWPORT, ButtonPopupPosition::Bottom);
+
+    assert_eq!(placement.position, ButtonPopupPosition::Top);
+}
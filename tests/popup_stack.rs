@@ -0,0 +1,124 @@
+#![cfg(feature = "frontend")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::component::{
+    popup::stack::{POPUP_BASE_Z_INDEX, POPUP_Z_INDEX_STEP},
+    PopupStack, PopupType,
+};
+use yew::{Callback, NodeRef};
+
+fn recorder(closed: &Rc<RefCell<Vec<&'static str>>>, name: &'static str) -> Callback<()> {
+    let closed = closed.clone();
+    Callback::from(move |_| closed.borrow_mut().push(name))
+}
+
+#[test]
+fn push_and_remove_keep_order() {
+    let stack = PopupStack::default();
+    assert!(stack.is_empty());
+
+    let first = stack.push(
+        PopupType::FullOverlay,
+        NodeRef::default(),
+        Callback::noop(),
+        0.0,
+    );
+    let second = stack.push(
+        PopupType::AtPoint(0, 0),
+        NodeRef::default(),
+        Callback::noop(),
+        0.0,
+    );
+
+    assert_eq!(stack.len(), 2);
+    assert!(stack.is_topmost(second));
+    assert!(!stack.is_topmost(first));
+
+    stack.remove(second);
+
+    assert_eq!(stack.top(), Some(first));
+
+    stack.remove(first);
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.top(), None);
+}
+
+#[test]
+fn z_index_increases_with_depth() {
+    let stack = PopupStack::default();
+
+    let first = stack.push(
+        PopupType::FullOverlay,
+        NodeRef::default(),
+        Callback::noop(),
+        0.0,
+    );
+    let second = stack.push(
+        PopupType::FullOverlay,
+        NodeRef::default(),
+        Callback::noop(),
+        0.0,
+    );
+
+    assert_eq!(stack.z_index(first), POPUP_BASE_Z_INDEX);
+    assert_eq!(
+        stack.z_index(second),
+        POPUP_BASE_Z_INDEX + POPUP_Z_INDEX_STEP
+    );
+
+    // Moves down once the popup below it is gone.
+    stack.remove(first);
+
+    assert_eq!(stack.z_index(second), POPUP_BASE_Z_INDEX);
+}
+
+#[test]
+fn close_top_only_closes_the_topmost() {
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let stack = PopupStack::default();
+
+    stack.push(
+        PopupType::FullOverlay,
+        NodeRef::default(),
+        recorder(&closed, "first"),
+        0.0,
+    );
+    let second = stack.push(
+        PopupType::Display,
+        NodeRef::default(),
+        recorder(&closed, "second"),
+        0.0,
+    );
+
+    stack.close_top();
+    assert_eq!(*closed.borrow(), ["second"]);
+
+    // Closing doesn't remove it, the owner does when it stops rendering.
+    assert!(stack.is_topmost(second));
+}
+
+#[test]
+fn close_all_goes_from_the_top_down() {
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let stack = PopupStack::default();
+
+    let first = stack.push(
+        PopupType::FullOverlay,
+        NodeRef::default(),
+        recorder(&closed, "first"),
+        0.0,
+    );
+    stack.push(
+        PopupType::AtPoint(1, 1),
+        NodeRef::default(),
+        recorder(&closed, "second"),
+        0.0,
+    );
+
+    stack.update(first, PopupType::FullOverlay, recorder(&closed, "updated"));
+    stack.close_all();
+
+    assert_eq!(*closed.borrow(), ["second", "updated"]);
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "backend")]
+
+use common::{
+    parse_book_id, Agent, BookId, BookPersonId, BookTagId, ClientId, IdType, ImageId, ImageIdType,
+    ImageType, Isbn, Language, MemberId, PersonId, Source, TagId, ThumbnailStore,
+};
+use sqlx::{Connection, Decode, Encode, Sqlite, SqliteConnection, Type};
+
+async fn connect() -> SqliteConnection {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    sqlx::query("CREATE TABLE item (value)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    conn
+}
+
+/// Inserts the value, reads it back and removes it again.
+async fn round_trip<V>(conn: &mut SqliteConnection, value: V) -> V
+where
+    V: for<'q> Encode<'q, Sqlite> + for<'r> Decode<'r, Sqlite> + Type<Sqlite> + Send + Unpin,
+{
+    sqlx::query("INSERT INTO item (value) VALUES (?)")
+        .bind(value)
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    let (read,): (V,) = sqlx::query_as("SELECT value FROM item")
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+
+    sqlx::query("DELETE FROM item")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    read
+}
+
+async fn decode_raw<V>(conn: &mut SqliteConnection, raw: &str) -> Result<V, sqlx::Error>
+where
+    V: for<'r> Decode<'r, Sqlite> + Type<Sqlite> + Send + Unpin,
+{
+    let (read,): (V,) = sqlx::query_as(&format!("SELECT {raw}"))
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(read)
+}
+
+#[tokio::test]
+async fn ids() {
+    let mut conn = connect().await;
+
+    assert_eq!(
+        round_trip(&mut conn, BookId::from(1)).await,
+        BookId::from(1)
+    );
+    assert_eq!(
+        round_trip(&mut conn, BookPersonId::from(2)).await,
+        BookPersonId::from(2)
+    );
+    assert_eq!(
+        round_trip(&mut conn, BookTagId::from(3)).await,
+        BookTagId::from(3)
+    );
+    assert_eq!(
+        round_trip(&mut conn, ImageId::from(4)).await,
+        ImageId::from(4)
+    );
+    assert_eq!(
+        round_trip(&mut conn, MemberId::from(5)).await,
+        MemberId::from(5)
+    );
+    assert_eq!(
+        round_trip(&mut conn, ClientId::from(6)).await,
+        ClientId::from(6)
+    );
+    assert_eq!(
+        round_trip(&mut conn, PersonId::from(7)).await,
+        PersonId::from(7)
+    );
+    assert_eq!(
+        round_trip(&mut conn, TagId::from(i64::MAX)).await,
+        TagId::from(i64::MAX)
+    );
+    assert_eq!(round_trip(&mut conn, BookId::none()).await, BookId::none());
+}
+
+#[tokio::test]
+async fn image_types() {
+    let mut conn = connect().await;
+
+    for type_of in [ImageType::Book, ImageType::Person] {
+        assert_eq!(round_trip(&mut conn, type_of).await, type_of);
+    }
+
+    for value in [
+        ImageIdType::new_book(BookId::from(12)),
+        ImageIdType::new_person(PersonId::from(34)),
+    ] {
+        assert_eq!(round_trip(&mut conn, value).await, value);
+    }
+
+    assert!(decode_raw::<ImageType>(&mut conn, "9").await.is_err());
+    assert!(decode_raw::<ImageIdType>(&mut conn, "'12'").await.is_err());
+    assert!(decode_raw::<ImageIdType>(&mut conn, "'12-9'")
+        .await
+        .is_err());
+    assert!(decode_raw::<ImageIdType>(&mut conn, "'a-0'").await.is_err());
+}
+
+#[tokio::test]
+async fn thumbnail_store() {
+    let mut conn = connect().await;
+
+    for value in [
+        ThumbnailStore::from("/images/abc.jpg"),
+        ThumbnailStore::None,
+    ] {
+        assert_eq!(round_trip(&mut conn, value.clone()).await, value);
+    }
+}
+
+#[tokio::test]
+async fn sources() {
+    let mut conn = connect().await;
+
+    let agent = Agent::new_static("google");
+    assert_eq!(round_trip(&mut conn, agent.clone()).await, agent);
+
+    let source = Source::try_from("amazon:B000FC2L1I").unwrap();
+    assert_eq!(round_trip(&mut conn, source.clone()).await, source);
+
+    // The value can contain the separator.
+    let source = Source::try_from("urn:isbn:9780306406157").unwrap();
+    assert_eq!(round_trip(&mut conn, source.clone()).await, source);
+
+    assert!(decode_raw::<Source>(&mut conn, "'missing separator'")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn languages() {
+    let mut conn = connect().await;
+
+    for value in [
+        Language::from_name("english"),
+        Language::from_name("german"),
+    ] {
+        assert_eq!(round_trip(&mut conn, value).await, value);
+    }
+
+    assert_eq!(Language::from_name("German").name(), "german");
+}
+
+#[tokio::test]
+async fn book_ids() {
+    let mut conn = connect().await;
+
+    for value in [
+        "isbn:9780306406157",
+        "urn:uuid:8e6c2b9a-1f4e-4a1c-9d7b-2b1f4d1b0c11",
+        "amazon_uk:B000FC2L1I",
+        "0306406152",
+    ] {
+        let value = parse_book_id(value);
+        assert_eq!(round_trip(&mut conn, value.clone()).await, value);
+    }
+
+    assert_eq!(
+        decode_raw::<IdType>(&mut conn, "'urn:isbn:0306406152'")
+            .await
+            .unwrap(),
+        IdType::Isbn(String::from("0306406152"))
+    );
+}
+
+#[tokio::test]
+async fn isbns() {
+    let mut conn = connect().await;
+
+    let isbn = Isbn::parse("0-306-40615-2").unwrap();
+
+    assert_eq!(&*isbn, "9780306406157");
+    assert_eq!(isbn.to_isbn_10().as_deref(), Some("0306406152"));
+    assert_eq!(
+        Isbn::parse("080442957x").unwrap().to_isbn_10().as_deref(),
+        Some("080442957X")
+    );
+    assert_eq!(Isbn::parse("9791032305690").unwrap().to_isbn_10(), None);
+    assert_eq!(round_trip(&mut conn, isbn.clone()).await, isbn);
+
+    // Stored as an ISBN-10 by something else.
+    assert_eq!(
+        decode_raw::<Isbn>(&mut conn, "'0306406152'").await.unwrap(),
+        isbn
+    );
+    assert!(decode_raw::<Isbn>(&mut conn, "'0306406153'").await.is_err());
+}
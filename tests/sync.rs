@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use common::{
+    api::librarian::{
+        ChangeType, GetChangesQuery, PublicAuthor, PublicBook, PublicChanges, SyncCursor,
+        SyncPosition, SyncToken,
+    },
+    error::Error,
+};
+
+fn at(millis: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(millis).unwrap()
+}
+
+fn book(id: usize, updated_at: i64) -> PublicBook {
+    PublicBook {
+        id,
+        title: Some(format!("Book {id}")),
+        clean_title: None,
+        description: None,
+        rating: 0.0,
+        thumb_url: None,
+        isbns: None,
+        display_author_id: None,
+        publisher: None,
+        author_ids: Vec::new(),
+        is_public: true,
+        edition_count: 1,
+        available_at: None,
+        language: 0,
+        created_at: at(0),
+        updated_at: at(updated_at),
+        deleted_at: None,
+    }
+}
+
+fn author(id: usize, updated_at: i64) -> PublicAuthor {
+    PublicAuthor {
+        id,
+        name: format!("Author {id}"),
+        description: None,
+        birth_date: None,
+        thumb_url: None,
+        other_names: Vec::new(),
+        updated_at: at(updated_at),
+        created_at: at(0),
+        deleted_at: None,
+    }
+}
+
+fn query(token: SyncToken) -> GetChangesQuery {
+    GetChangesQuery {
+        token: Some(token),
+        since: None,
+        limit: None,
+        server_id: String::new(),
+    }
+}
+
+#[test]
+fn token_round_trip() {
+    let cursor = SyncCursor {
+        books: Some(SyncPosition {
+            updated_at: at(1_680_352_200_123),
+            id: 4,
+        }),
+        authors: None,
+    };
+
+    assert_eq!(SyncToken::from(cursor).cursor(), Some(cursor));
+    assert_eq!(SyncToken::default().cursor(), None);
+}
+
+#[test]
+fn token_before_1970() {
+    let cursor = SyncCursor {
+        books: Some(SyncPosition {
+            updated_at: at(-86_400_000),
+            id: 12,
+        }),
+        authors: Some(SyncPosition {
+            updated_at: at(-1),
+            id: 3,
+        }),
+    };
+
+    let token = SyncToken::from(cursor);
+
+    assert!(token.to_string().starts_with('-'));
+    assert_eq!(query(token).cursor().unwrap(), cursor);
+}
+
+#[test]
+fn invalid_token_is_an_error() {
+    for token in ["garbage", "12.4", "12.4_abc", "12_"] {
+        let mut query = query(token.parse().unwrap());
+        query.since = Some(at(1_000));
+
+        assert!(matches!(query.cursor(), Err(Error::InvalidSyncToken(_))));
+    }
+
+    // An empty token is the same as not sending one.
+    assert_eq!(
+        query(SyncToken::default()).cursor().unwrap(),
+        SyncCursor::default()
+    );
+}
+
+#[test]
+fn tables_are_paged_separately() {
+    // The books are behind the authors. They must not be skipped by the next sync.
+    let changes = PublicChanges::new(
+        vec![book(1, 100), book(2, 200)],
+        vec![author(7, 5_000)],
+        SyncCursor::default(),
+        true,
+    );
+
+    assert!(changes.has_more);
+
+    let cursor = query(changes.next_token).cursor().unwrap();
+
+    assert_eq!(
+        cursor.get(ChangeType::Book),
+        Some(SyncPosition {
+            updated_at: at(200),
+            id: 2
+        })
+    );
+    assert_eq!(
+        cursor.get(ChangeType::Author),
+        Some(SyncPosition {
+            updated_at: at(5_000),
+            id: 7
+        })
+    );
+
+    // Only books changed since. Authors keep their position.
+    let changes = PublicChanges::new(vec![book(3, 300)], Vec::new(), cursor, false);
+
+    let next = query(changes.next_token).cursor().unwrap();
+
+    assert_eq!(next.books.map(|v| v.id), Some(3));
+    assert_eq!(next.authors, cursor.authors);
+}
+
+#[test]
+fn tombstones_keep_the_updated_position() {
+    let mut deleted = book(2, 200);
+    deleted.deleted_at = Some(at(9_000));
+
+    let changes = PublicChanges::new(
+        vec![book(1, 100), deleted],
+        Vec::new(),
+        SyncCursor::default(),
+        false,
+    );
+
+    assert_eq!(changes.books.len(), 1);
+    assert_eq!(changes.deleted.len(), 1);
+    assert_eq!(changes.deleted[0].type_of, ChangeType::Book);
+    assert_eq!(changes.deleted[0].deleted_at, at(9_000));
+
+    assert_eq!(
+        changes.next_token.cursor().and_then(|v| v.books),
+        Some(SyncPosition {
+            updated_at: at(200),
+            id: 2
+        })
+    );
+}
+
+#[test]
+fn since_starts_both_tables() {
+    let query = GetChangesQuery {
+        token: None,
+        since: Some(at(1_000)),
+        limit: None,
+        server_id: String::new(),
+    };
+
+    let position = Some(SyncPosition {
+        updated_at: at(1_000),
+        id: 0,
+    });
+
+    assert_eq!(
+        query.cursor().unwrap(),
+        SyncCursor {
+            books: position,
+            authors: position
+        }
+    );
+}
+
+#[test]
+fn apply_to_mirror() {
+    let mut books = HashMap::new();
+    let mut authors = HashMap::new();
+
+    books.insert(1, book(1, 500));
+    books.insert(2, book(2, 100));
+    authors.insert(1, author(1, 100));
+
+    let mut old = book(1, 400);
+    old.title = Some(String::from("Old"));
+
+    let mut deleted = author(1, 200);
+    deleted.deleted_at = Some(at(200));
+
+    PublicChanges::new(
+        vec![old, book(3, 100)],
+        vec![deleted, author(2, 200)],
+        SyncCursor::default(),
+        false,
+    )
+    .apply_to(&mut books, &mut authors);
+
+    // Older than the one we have.
+    assert_eq!(books[&1].title.as_deref(), Some("Book 1"));
+    assert!(books.contains_key(&2));
+    assert!(books.contains_key(&3));
+
+    assert!(!authors.contains_key(&1));
+    assert!(authors.contains_key(&2));
+}
@@ -0,0 +1,96 @@
+use common::util::{
+    parse_roman_numeral, slugify, strip_html, title_case, truncate_on_indices,
+    truncate_with_ellipsis,
+};
+
+#[test]
+fn strip_tags_and_entities() {
+    assert_eq!(
+        strip_html("<p>The <b>first</b> book.</p><p>Fish &amp; Chips&hellip;</p>"),
+        "The first book.\nFish & Chips…"
+    );
+    assert_eq!(strip_html("Line<br/>Break"), "Line\nBreak");
+    assert_eq!(
+        strip_html("<!DOCTYPE html>Hi<script>alert('<p>')</script><!-- note --> there"),
+        "Hi there"
+    );
+    assert_eq!(strip_html("<STYLE>p {}</Style>Text"), "Text");
+    assert_eq!(strip_html(r#"<a title="a > b">x</a>"#), "x");
+    assert_eq!(strip_html("<img alt='1 > 0' src=x>y"), "y");
+    assert_eq!(strip_html(r#"<a title = "x>">z</a>"#), "z");
+}
+
+#[test]
+fn strip_keeps_comparisons() {
+    assert_eq!(strip_html("x < y and z > w"), "x < y and z > w");
+    assert_eq!(strip_html("a <b> c"), "a c");
+    assert_eq!(strip_html("1 <2 and 3> 2"), "1 <2 and 3> 2");
+    assert_eq!(strip_html("a < b"), "a < b");
+    assert_eq!(strip_html("<i>x</i> <= y"), "x <= y");
+    assert_eq!(strip_html("AT&T &unknown;"), "AT&T &unknown;");
+}
+
+#[test]
+fn truncate_graphemes() {
+    let mut value = String::from("e\u{301}e\u{301}e\u{301}");
+    truncate_on_indices(&mut value, 2);
+    assert_eq!(value, "e\u{301}e\u{301}");
+
+    let mut value = String::from("short");
+    truncate_on_indices(&mut value, 10);
+    assert_eq!(value, "short");
+}
+
+#[test]
+fn truncate_ellipsis() {
+    assert_eq!(truncate_with_ellipsis("Short", 10), "Short");
+    assert_eq!(truncate_with_ellipsis("Anything", 0), "");
+    assert_eq!(
+        truncate_with_ellipsis("The quick brown fox jumps", 18),
+        "The quick brown…"
+    );
+    // Too far back to a word boundary.
+    assert_eq!(
+        truncate_with_ellipsis("Supercalifragilistic", 10),
+        "Supercali…"
+    );
+    assert_eq!(truncate_with_ellipsis("Hello, world", 8), "Hello…");
+    assert_eq!(truncate_with_ellipsis("👍🏽👍🏽👍🏽", 2), "👍🏽…");
+}
+
+#[test]
+fn slugs() {
+    assert_eq!(slugify("Les Misérables"), "les-miserables");
+    assert_eq!(
+        slugify("  The Hitchhiker's Guide!! "),
+        "the-hitchhikers-guide"
+    );
+    assert_eq!(slugify("<i>Dune</i>: Messiah"), "dune-messiah");
+    assert_eq!(slugify("---"), "");
+}
+
+#[test]
+fn title_casing() {
+    assert_eq!(title_case("the lord of the rings"), "The Lord of the Rings");
+    assert_eq!(title_case("dune: the sequel"), "Dune: The Sequel");
+    assert_eq!(title_case("WAR AND PEACE"), "War and Peace");
+    assert_eq!(
+        title_case("my iPhone and the McCoys"),
+        "My iPhone and the McCoys"
+    );
+    assert_eq!(title_case("what it is for"), "What It Is For");
+}
+
+#[test]
+fn roman_numerals() {
+    assert_eq!(parse_roman_numeral("II"), Some(2));
+    assert_eq!(parse_roman_numeral("xiv"), Some(14));
+    assert_eq!(parse_roman_numeral("MCMXCIX"), Some(1999));
+
+    assert_eq!(parse_roman_numeral("Ill"), None);
+    assert_eq!(parse_roman_numeral("IIII"), None);
+    assert_eq!(parse_roman_numeral("IXI"), None);
+    assert_eq!(parse_roman_numeral("IL"), None);
+    assert_eq!(parse_roman_numeral(""), None);
+    assert_eq!(parse_roman_numeral("2"), None);
+}
@@ -0,0 +1,63 @@
+use common::{get_language_id, normalize_title, sort_title};
+
+#[test]
+fn removes_series_and_edition_information() {
+    assert_eq!(
+        normalize_title("Leviathan Wakes: The Expanse, Book 1 (Kindle Edition)"),
+        "Leviathan Wakes"
+    );
+    assert_eq!(
+        normalize_title("The Way of Kings (The Stormlight Archive, #1)"),
+        "The Way of Kings"
+    );
+    assert_eq!(normalize_title("Dune, 40th Anniversary Edition"), "Dune");
+    assert_eq!(normalize_title("Foundation, Book 2"), "Foundation");
+    assert_eq!(normalize_title("Dune Messiah, Vol. II"), "Dune Messiah");
+    assert_eq!(normalize_title("It (Part II)"), "It");
+    assert_eq!(normalize_title("Mistborn #2.5 [Illustrated]"), "Mistborn");
+    assert_eq!(normalize_title("Title:Subtitle"), "Title");
+    assert_eq!(normalize_title("  Gone   Girl  "), "Gone Girl");
+}
+
+#[test]
+fn keeps_meaningful_parts() {
+    // Brackets which aren't series information.
+    assert_eq!(
+        normalize_title("Alice (in Wonderland)"),
+        "Alice (in Wonderland)"
+    );
+    assert_eq!(normalize_title("Catch-22"), "Catch-22");
+    // Series markers are only removed from comma separated or bracketed groups.
+    assert_eq!(
+        normalize_title("The Godfather Part II"),
+        "The Godfather Part II"
+    );
+    assert_eq!(normalize_title("Foundation Book 2"), "Foundation Book 2");
+    // Not a roman numeral.
+    assert_eq!(normalize_title("Sorrow, Part Ill"), "Sorrow, Part Ill");
+    // Only "Book 2" would be left which isn't worth losing the title over.
+    assert_eq!(normalize_title("Book 2"), "Book 2");
+    assert_eq!(normalize_title("(Book 1)"), "(Book 1)");
+}
+
+#[test]
+fn sort_titles() {
+    let english = get_language_id("english");
+    let french = get_language_id("french");
+    let german = get_language_id("german");
+
+    assert_eq!(sort_title("The Hobbit", english), "Hobbit, The");
+    assert_eq!(
+        sort_title("A Game of Thrones", english),
+        "Game of Thrones, A"
+    );
+    assert_eq!(sort_title("\"The Stand\"", english), "Stand, The");
+    assert_eq!(sort_title("Anthem", english), "Anthem");
+    assert_eq!(sort_title("The", english), "The");
+
+    assert_eq!(sort_title("L'Étranger", french), "Étranger, L'");
+    assert_eq!(sort_title("Les Misérables", french), "Misérables, Les");
+    assert_eq!(sort_title("Die Verwandlung", german), "Verwandlung, Die");
+    // Articles of other languages are left alone.
+    assert_eq!(sort_title("Die Hard", english), "Die Hard");
+}
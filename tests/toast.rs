@@ -0,0 +1,38 @@
+#![cfg(feature = "frontend")]
+
+use common::{
+    api::{ApiErrorResponse, ErrorCodeResponse},
+    component::{Toast, ToastLevel},
+};
+use yew::Callback;
+
+#[test]
+fn builder_defaults_to_level_timeout() {
+    let toast = Toast::success("Metadata updated");
+
+    assert_eq!(toast.level, ToastLevel::Success);
+    assert_eq!(toast.message, "Metadata updated");
+    assert_eq!(toast.timeout, Some(ToastLevel::Success.default_timeout()));
+    assert!(toast.title.is_none());
+    assert!(toast.action.is_none());
+
+    let toast = Toast::warning("Slow down")
+        .with_title("Rate Limited")
+        .with_action("Retry", Callback::noop())
+        .persistent();
+
+    assert_eq!(toast.title.as_deref(), Some("Rate Limited"));
+    assert_eq!(toast.action.map(|v| v.label), Some("Retry".into()));
+    assert_eq!(toast.timeout, None);
+}
+
+#[test]
+fn api_error_becomes_error_toast() {
+    let error = ApiErrorResponse::new_with_code("Invalid Book Id", ErrorCodeResponse::InvalidId);
+
+    let toast = Toast::from(&error);
+
+    assert_eq!(toast.level, ToastLevel::Error);
+    assert_eq!(toast.message, "Invalid Book Id");
+    assert_eq!(toast.timeout, Some(ToastLevel::Error.default_timeout()));
+}